@@ -0,0 +1,175 @@
+use crate::*;
+
+//how the contract owner's access list is read
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AccessMode {
+    //everyone can use the marketplace except the listed accounts
+    Blocklist,
+    //only the listed accounts can use the marketplace
+    Allowlist,
+}
+
+#[near_bindgen]
+impl Contract {
+    /*
+        The contract owner switches between a blocklist and an allowlist. The list itself is kept,
+        so switching modes bans or allows the same accounts the other way around.
+    */
+    pub fn set_access_mode(&mut self, mode: AccessMode) {
+        self.assert_contract_owner();
+        self.access_mode = mode;
+    }
+
+    pub fn get_access_mode(&self) -> AccessMode {
+        self.access_mode.clone()
+    }
+
+    //the contract owner manages the accounts in the access list
+    pub fn add_to_access_list(&mut self, account_id: AccountId) {
+        self.assert_contract_owner();
+        self.access_list.insert(&account_id);
+    }
+
+    pub fn remove_from_access_list(&mut self, account_id: AccountId) {
+        self.assert_contract_owner();
+        self.access_list.remove(&account_id);
+    }
+
+    pub fn get_access_list(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<AccountId> {
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.access_list.iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    //banned accounts can't enter the marketplace and their requests are frozen
+    pub fn is_banned(&self, account_id: AccountId) -> bool {
+        self.internal_is_banned(&account_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn test_access_list_and_frozen_requests() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        contract.add_ft_token(accounts(4));
+        create_request_as(&mut context, &mut contract, accounts(1), "Necesito ayuda con Rust");
+        set_caller(&mut context, accounts(4));
+        let fund_msg = r#"{"action": "fund", "request_id": 0}"#.to_string();
+        contract.ft_on_transfer(accounts(1), U128(100), fund_msg.clone());
+
+        // a banned owner keeps the request, but it can't be funded anymore
+        set_caller(&mut context, accounts(0));
+        contract.add_to_access_list(accounts(1));
+        assert!(contract.is_banned(accounts(1)));
+        assert!(contract.get_request(0).is_some());
+        set_caller(&mut context, accounts(4));
+        assert_eq!(unused_tokens(contract.ft_on_transfer(accounts(1), U128(50), fund_msg)), U128(50));
+        assert_eq!(contract.get_request(0).unwrap().ft_bounty, U128(100));
+
+        // in allowlist mode only the listed accounts get in
+        set_caller(&mut context, accounts(0));
+        contract.set_access_mode(AccessMode::Allowlist);
+        assert!(!contract.is_banned(accounts(1)));
+        assert!(contract.is_banned(accounts(2)));
+        assert_eq!(contract.get_access_list(None, None), vec![accounts(1)]);
+    }
+
+    #[test]
+    fn test_marketplace_entry_points_check_access() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        create_request_as(&mut context, &mut contract, accounts(1), "Necesito ayuda con Rust");
+        let test_name = "test_marketplace_entry_points_check_access";
+
+        // accounts(2) has no profile yet
+        set_caller_with_deposit(&mut context, accounts(2), MINT_STORAGE_COST);
+        assert_contract_panics_case(module_path!(), test_name, "create without profile", "Debes crear un perfil", || {
+            contract.create_request("Necesito ayuda".to_string(), None, None, None, None, None);
+        });
+        assert_contract_panics_case(module_path!(), test_name, "attend without profile", "Debes crear un perfil", || {
+            contract.attend_request(0, None);
+        });
+
+        // banned accounts keep their profile but can't get in
+        register(&mut contract, &accounts(2));
+        set_caller(&mut context, accounts(0));
+        contract.add_to_access_list(accounts(2));
+        set_caller_with_deposit(&mut context, accounts(2), MINT_STORAGE_COST);
+        assert_contract_panics_case(module_path!(), test_name, "create banned", "no tiene acceso", || {
+            contract.create_request("Necesito ayuda".to_string(), None, None, None, None, None);
+        });
+        assert_contract_panics_case(module_path!(), test_name, "attend banned", "no tiene acceso", || {
+            contract.attend_request(0, None);
+        });
+
+        // in allowlist mode accounts(3) is not listed
+        register(&mut contract, &accounts(3));
+        set_caller(&mut context, accounts(0));
+        contract.set_access_mode(AccessMode::Allowlist);
+        contract.add_to_access_list(accounts(1));
+        set_caller_with_deposit(&mut context, accounts(3), MINT_STORAGE_COST);
+        assert_contract_panics_case(module_path!(), test_name, "create not allowlisted", "no tiene acceso", || {
+            contract.create_request("Necesito ayuda".to_string(), None, None, None, None, None);
+        });
+        assert_contract_panics_case(module_path!(), test_name, "attend not allowlisted", "no tiene acceso", || {
+            contract.attend_request(0, None);
+        });
+
+        set_caller_with_deposit(&mut context, accounts(2), MINT_STORAGE_COST);
+        contract.attend_request(0, None);
+        assert_eq!(contract.request_applications(0, None, None).len(), 1);
+    }
+
+    #[test]
+    fn test_accept_application_checks_the_applicant() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        create_request_as(&mut context, &mut contract, accounts(1), "Necesito ayuda con Rust");
+        apply_to_request(&mut context, &mut contract, accounts(2), 0);
+
+        // the applicant was banned after applying
+        set_caller(&mut context, accounts(0));
+        contract.add_to_access_list(accounts(2));
+        set_caller(&mut context, accounts(1));
+        assert_contract_panics(module_path!(), "test_accept_application_checks_the_applicant", "no tiene acceso", || {
+            contract.accept_application(0, accounts(2));
+        });
+    }
+
+    #[test]
+    fn test_frozen_requests_only_block_the_owner() {
+        let mut context = get_context(accounts(0));
+        let mut contract = contract_with_attended_request(&mut context);
+        create_request_as(&mut context, &mut contract, accounts(1), "Necesito ayuda con NEAR");
+        apply_to_request(&mut context, &mut contract, accounts(3), 1);
+        set_caller(&mut context, accounts(1));
+        contract.accept_application(1, accounts(3));
+
+        set_caller(&mut context, accounts(0));
+        contract.add_to_access_list(accounts(1));
+        set_caller(&mut context, accounts(1));
+        assert_contract_panics(module_path!(), "test_frozen_requests_only_block_the_owner", "congelada", || {
+            contract.finish_request(0);
+        });
+
+        // the helpers can still leave and timed out helpers can still be removed
+        set_caller(&mut context, accounts(2));
+        contract.withdraw_from_request(0);
+        assert!(contract.get_request(0).unwrap().status == RequestStatus::Open);
+
+        testing_env!(context.predecessor_account_id(accounts(4)).block_timestamp(DEFAULT_WORK_TIMEOUT).build());
+        contract.expire_request(1);
+        assert!(contract.get_request(1).unwrap().status == RequestStatus::Open);
+    }
+}
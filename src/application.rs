@@ -0,0 +1,135 @@
+use crate::*;
+
+//max number of pending applications a request can hold
+pub const MAX_APPLICATIONS_PER_REQUEST: u64 = 20;
+//max length (in characters) of the pitch sent with an application
+pub const MAX_PITCH_LEN: usize = 280;
+
+//a helper offering to attend an open request
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Application {
+    pub applicant_id: AccountId,
+    pub pitch: String,
+    pub applied_at: U64,
+    //storage deposit paid by the applicant, refunded when the application is removed
+    pub deposit: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    //pending applications of a request
+    pub fn request_applications(
+        &self,
+        request_id: u64,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Application> {
+        let applications = if let Some(applications) = self.applications_per_request.get(&request_id) {
+            applications
+        } else {
+            return vec![];
+        };
+
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        applications.values()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    //the applicant takes back their application and gets the storage deposit refunded
+    pub fn withdraw_application(&mut self, request_id: u64) -> String {
+        let applicant_id = env::signer_account_id();
+
+        if self.internal_remove_application(&request_id, &applicant_id).is_none() {
+            env::panic_str("No tienes una postulación en esta solicitud");
+        }
+
+        "Postulación retirada con éxito".to_string()
+    }
+
+    //the owner picks one of the applicants, the other applications are rejected once every seat is taken
+    pub fn accept_application(&mut self, request_id: u64, applicant_id: AccountId) -> Option<Request> {
+        if let Some(mut request_data) = self.requests.get(&request_id) {
+            if request_data.owner_id != env::signer_account_id() {
+                env::panic_str("Solo el creador de la solicitud puede elegir quién la atiende");
+            }
+
+            if !self.internal_accepts_helpers(&request_data) {
+                env::panic_str("La solicitud ya fué atendida o fué cancelada");
+            }
+
+            if self.internal_deadline_passed(&request_data) {
+                env::panic_str("La solicitud ya venció");
+            }
+
+            self.assert_request_not_frozen(&request_data);
+            self.assert_can_use_marketplace(&applicant_id);
+
+            if self.internal_remove_application(&request_id, &applicant_id).is_none() {
+                env::panic_str("La cuenta no se postuló a esta solicitud");
+            }
+
+            request_data.participants.push(Participant {
+                account_id: applicant_id.clone(),
+                joined_at: U64(env::block_timestamp()),
+                completed: false,
+                commitment_owner: None,
+                commitment_helper: None,
+                review_owner: None,
+                review_helper: None,
+            });
+            if (request_data.participants.len() as u64) >= request_data.capacity {
+                self.internal_clear_applications(&request_id);
+            }
+
+            let previous_status = request_data.status.clone();
+            let new_request = Request {
+                helper: request_data.helper.clone().or_else(|| Some(applicant_id.clone())),
+                status: RequestStatus::InProgress,
+                attended_at: request_data.attended_at.or_else(|| Some(U64(env::block_timestamp()))),
+                ..request_data
+            };
+
+            self.requests.insert(&request_id, &new_request);
+            self.internal_update_status_index(&new_request, Some(&previous_status));
+            self.internal_add_request_to_helper(&applicant_id, &request_id);
+            self.internal_update_reputation(&applicant_id, |reputation| reputation.requests_attended += 1);
+
+            Some(new_request)
+        } else {
+            env::panic_str("No existe la solicitud");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+
+    #[test]
+    fn test_owner_picks_helper_among_applicants() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        create_request_as(&mut context, &mut contract, accounts(1), "Necesito ayuda con Rust");
+
+        apply_to_request(&mut context, &mut contract, accounts(2), 0);
+        apply_to_request(&mut context, &mut contract, accounts(3), 0);
+        let applications = contract.request_applications(0, None, None);
+        assert_eq!(applications.len(), 2);
+        assert!(applications[0].deposit.0 > 0);
+        assert!(contract.get_request(0).unwrap().status == RequestStatus::Open);
+
+        set_caller(&mut context, accounts(1));
+        contract.accept_application(0, accounts(3));
+        let request = contract.get_request(0).unwrap();
+        assert!(request.status == RequestStatus::InProgress);
+        assert_eq!(request.helper, Some(accounts(3)));
+        // everyone else was rejected
+        assert_eq!(contract.request_applications(0, None, None).len(), 0);
+    }
+}
@@ -1,7 +1,10 @@
 use crate::*;
-use near_sdk::{ext_contract};
+use near_sdk::{ext_contract, Gas};
 
-pub trait NonFungibleTokenCore {
+const GAS_FOR_NFT_APPROVE: Gas = Gas(10_000_000_000_000);
+const NO_DEPOSIT: Balance = 0;
+
+pub trait NonFungibleTokenApproval {
     //approve an account ID to transfer a token on your behalf
     fn nft_approve(&mut self, token_id: TokenId, account_id: AccountId, msg: Option<String>);
 
@@ -33,7 +36,7 @@ trait NonFungibleTokenApprovalsReceiver {
 }
 
 #[near_bindgen]
-impl NonFungibleTokenCore for Contract {
+impl NonFungibleTokenApproval for Contract {
 
     //allow a specific account ID to approve a token on your behalf
     #[payable]
@@ -54,6 +57,11 @@ impl NonFungibleTokenCore for Contract {
             "Predecessor must be the token owner."
         );
 
+        //an approved account couldn't transfer a soulbound token anyway
+        if self.soulbound_tokens.contains(&token_id) {
+            env::panic_str("Este token es intransferible");
+        }
+
         //get the next approval ID if we need a new approval
         let approval_id: u64 = token.next_approval_id;
 
@@ -84,14 +92,16 @@ impl NonFungibleTokenCore for Contract {
         //if some message was passed into the function, we initiate a cross contract call on the
         //account we're giving access to. 
         if let Some(msg) = msg {
-            // Defaulting GAS weight to 1, no attached deposit, and no static GAS to attach.
-            ext_non_fungible_approval_receiver::ext(account_id)
-                .nft_on_approve(
-                    token_id, 
-                    token.owner_id, 
-                    approval_id, 
-                    msg
-                ).as_return();
+            ext_non_fungible_approval_receiver::nft_on_approve(
+                token_id,
+                token.owner_id,
+                approval_id,
+                msg,
+                account_id, //contract account to make the call to
+                NO_DEPOSIT, //attached deposit
+                env::prepaid_gas() - GAS_FOR_NFT_APPROVE, //attached GAS
+            )
+            .as_return();
         }
     }
 
@@ -0,0 +1,387 @@
+use crate::*;
+use near_sdk::{ext_contract, PromiseResult};
+
+pub(crate) const GAS_FOR_RESOLVE_BOUNTY_TRANSFER: Gas = Gas(10_000_000_000_000);
+pub(crate) const GAS_FOR_FT_TRANSFER: Gas = Gas(10_000_000_000_000);
+
+//state of the NEAR locked as a request bounty
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum EscrowStatus {
+    //the request was created without a bounty
+    NoBounty,
+    //the bounty is held by the contract
+    Locked,
+    //a transfer of the bounty is on its way
+    Transferring,
+    //the bounty was paid to the helper
+    Paid,
+    //the bounty was returned to the owner
+    Refunded,
+}
+
+//message sent with ft_transfer_call to fund a request bounty
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum FtBountyMsg {
+    //create a new request funded with the transferred tokens, its storage is paid from the balance of deposit_request_storage
+    Create {
+        description: String,
+        deadline: Option<U64>,
+        work_timeout: Option<U64>,
+        tags: Option<Vec<String>>,
+    },
+    //add the transferred tokens to the bounty of an existing request
+    Fund { request_id: u64 },
+}
+
+#[ext_contract(ext_bounty)]
+trait BountyResolver {
+    fn resolve_bounty_transfer(&mut self, request_id: u64) -> bool;
+    fn resolve_ft_bounty_transfer(&mut self, request_id: u64) -> bool;
+}
+
+#[near_bindgen]
+impl Contract {
+    //retries the payout or the refund of a bounty whose transfer failed. Anyone can call it
+    pub fn release_bounty(&mut self, request_id: u64) -> Promise {
+        let mut request = self.requests.get(&request_id)
+            .unwrap_or_else(|| env::panic_str("No existe la solicitud"));
+
+        if request.escrow != EscrowStatus::Locked {
+            env::panic_str("La solicitud no tiene una recompensa retenida");
+        }
+
+        match request.status {
+            RequestStatus::Complete | RequestStatus::Cancelled | RequestStatus::Expired => self.internal_release_bounty(&mut request),
+            _ => env::panic_str("La recompensa se libera cuando la solicitud finaliza, se cancela o vence"),
+        }
+    }
+
+    //restores the escrow if the transfer of the bounty failed
+    #[private]
+    pub fn resolve_bounty_transfer(&mut self, request_id: u64) -> bool {
+        let mut request = self.requests.get(&request_id).expect("No existe la solicitud");
+
+        let transferred = matches!(env::promise_result(0), PromiseResult::Successful(_));
+
+        request.escrow = if !transferred {
+            log!("No se pudo transferir la recompensa de la solicitud {}, queda retenida", request_id);
+            EscrowStatus::Locked
+        } else if request.status == RequestStatus::Complete {
+            EscrowStatus::Paid
+        } else {
+            EscrowStatus::Refunded
+        };
+        self.requests.insert(&request_id, &request);
+
+        transferred
+    }
+
+    //retries the payout or the refund of a fungible token bounty whose transfer failed. Anyone can call it
+    pub fn release_ft_bounty(&mut self, request_id: u64) -> Promise {
+        let mut request = self.requests.get(&request_id)
+            .unwrap_or_else(|| env::panic_str("No existe la solicitud"));
+
+        if request.ft_escrow != EscrowStatus::Locked {
+            env::panic_str("La solicitud no tiene una recompensa retenida");
+        }
+
+        match request.status {
+            RequestStatus::Complete | RequestStatus::Cancelled | RequestStatus::Expired => self.internal_release_ft_bounty(&mut request),
+            _ => env::panic_str("La recompensa se libera cuando la solicitud finaliza, se cancela o vence"),
+        }
+    }
+
+    //restores the fungible token escrow if ft_transfer failed
+    #[private]
+    pub fn resolve_ft_bounty_transfer(&mut self, request_id: u64) -> bool {
+        let mut request = self.requests.get(&request_id).expect("No existe la solicitud");
+
+        let transferred = matches!(env::promise_result(0), PromiseResult::Successful(_));
+
+        request.ft_escrow = if !transferred {
+            log!("No se pudo transferir la recompensa en tokens de la solicitud {}, queda retenida", request_id);
+            EscrowStatus::Locked
+        } else if request.status == RequestStatus::Complete {
+            EscrowStatus::Paid
+        } else {
+            EscrowStatus::Refunded
+        };
+        self.requests.insert(&request_id, &request);
+
+        transferred
+    }
+
+    /*
+        NEP-141 receiver. The predecessor is the token contract and msg says which request to fund.
+        Returns the amount that was not used, which the token contract gives back to the sender.
+    */
+    pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+        let ft_token_id = env::predecessor_account_id();
+
+        if !self.ft_token_whitelist.contains(&ft_token_id) {
+            log!("El token {} no está permitido para recompensas", ft_token_id);
+            return PromiseOrValue::Value(amount);
+        }
+
+        let bounty_msg: FtBountyMsg = near_sdk::serde_json::from_str(&msg)
+            .unwrap_or_else(|_| env::panic_str("Mensaje inválido para financiar una solicitud"));
+
+        match bounty_msg {
+            FtBountyMsg::Create { description, deadline, work_timeout, tags } => {
+                if !self.internal_can_use_marketplace(&sender_id) {
+                    log!("La cuenta {} necesita un perfil y acceso al marketplace", sender_id);
+                    return PromiseOrValue::Value(amount);
+                }
+
+                let storage_balance = self.request_storage_balances.get(&sender_id).unwrap_or(0);
+                if storage_balance == 0 {
+                    log!("La cuenta {} debe depositar NEAR con deposit_request_storage para pagar el almacenamiento", sender_id);
+                    return PromiseOrValue::Value(amount);
+                }
+
+                let initial_storage_usage = env::storage_usage();
+                let mut request = self.internal_new_request(&sender_id, description);
+                self.internal_set_deadlines(&mut request, deadline, work_timeout);
+                self.internal_set_tags(&mut request, tags.unwrap_or_default());
+                request.ft_token_id = Some(ft_token_id);
+                request.ft_bounty = amount;
+                request.ft_escrow = EscrowStatus::Locked;
+                self.internal_add_request(&request);
+
+                //the deposit has a fixed size, so recording it doesn't change the storage used
+                let storage_cost = env::storage_byte_cost() * Balance::from(env::storage_usage() - initial_storage_usage);
+                request.storage_deposit = U128(storage_cost);
+                self.requests.insert(&request.request_id, &request);
+
+                //the panic drops the request and the token contract gives the whole amount back
+                if storage_cost > storage_balance {
+                    env::panic_str(&format!(
+                        "El depósito de almacenamiento no alcanza, se necesitan {} yoctoNEAR",
+                        storage_cost
+                    ));
+                }
+                self.request_storage_balances.insert(&sender_id, &(storage_balance - storage_cost));
+            }
+            FtBountyMsg::Fund { request_id } => {
+                let mut request = if let Some(request) = self.requests.get(&request_id) {
+                    request
+                } else {
+                    log!("No existe la solicitud {}", request_id);
+                    return PromiseOrValue::Value(amount);
+                };
+
+                //only the owner funds a request since refunds go back to them
+                if request.owner_id != sender_id {
+                    log!("Solo el creador de la solicitud puede financiarla");
+                    return PromiseOrValue::Value(amount);
+                }
+
+                if self.internal_is_banned(&request.owner_id) {
+                    log!("La solicitud {} está congelada", request_id);
+                    return PromiseOrValue::Value(amount);
+                }
+
+                if request.status != RequestStatus::Open && request.status != RequestStatus::InProgress {
+                    log!("La solicitud {} ya no acepta recompensas", request_id);
+                    return PromiseOrValue::Value(amount);
+                }
+
+                //bounties are paid to a single helper
+                if request.capacity > 1 {
+                    log!("La solicitud {} es grupal y no acepta recompensas", request_id);
+                    return PromiseOrValue::Value(amount);
+                }
+
+                //a request holds a single kind of token
+                if request.ft_token_id.is_some() && request.ft_token_id != Some(ft_token_id.clone()) {
+                    log!("La solicitud {} ya tiene una recompensa en otro token", request_id);
+                    return PromiseOrValue::Value(amount);
+                }
+
+                //a transfer of a previous bounty may still be in flight
+                if request.ft_escrow != EscrowStatus::NoBounty && request.ft_escrow != EscrowStatus::Locked {
+                    log!("La recompensa de la solicitud {} ya fué liberada", request_id);
+                    return PromiseOrValue::Value(amount);
+                }
+
+                request.ft_token_id = Some(ft_token_id);
+                request.ft_bounty = U128(request.ft_bounty.0 + amount.0);
+                request.ft_escrow = EscrowStatus::Locked;
+                self.requests.insert(&request_id, &request);
+            }
+        }
+
+        PromiseOrValue::Value(U128(0))
+    }
+
+    /*
+        NEAR the caller sets aside to pay the storage of the requests created with ft_transfer_call. The first
+        deposit also pays for the entry that keeps the balance. Returns the new balance.
+    */
+    #[payable]
+    pub fn deposit_request_storage(&mut self) -> U128 {
+        let account_id = env::signer_account_id();
+        let initial_storage_usage = env::storage_usage();
+
+        let balance = self.request_storage_balances.get(&account_id).unwrap_or(0);
+        self.request_storage_balances.insert(&account_id, &balance);
+        let entry_cost = env::storage_byte_cost() * Balance::from(env::storage_usage() - initial_storage_usage);
+        if env::attached_deposit() <= entry_cost {
+            env::panic_str(&format!("Debes adjuntar más de {} yoctoNEAR", entry_cost));
+        }
+
+        let balance = balance + env::attached_deposit() - entry_cost;
+        self.request_storage_balances.insert(&account_id, &balance);
+
+        U128(balance)
+    }
+
+    //gives back the unused storage balance of the caller along with the storage of its entry
+    #[payable]
+    pub fn withdraw_request_storage(&mut self) -> U128 {
+        assert_one_yocto();
+        let account_id = env::signer_account_id();
+        let initial_storage_usage = env::storage_usage();
+
+        let balance = self.request_storage_balances.remove(&account_id)
+            .unwrap_or_else(|| env::panic_str("No tienes un depósito de almacenamiento"));
+        let refund = balance + env::storage_byte_cost() * Balance::from(initial_storage_usage - env::storage_usage());
+        Promise::new(env::predecessor_account_id()).transfer(refund);
+
+        U128(refund)
+    }
+
+    pub fn request_storage_balance(&self, account_id: AccountId) -> U128 {
+        U128(self.request_storage_balances.get(&account_id).unwrap_or(0))
+    }
+
+    //the contract owner manages the tokens accepted for bounties
+    pub fn add_ft_token(&mut self, ft_token_id: AccountId) {
+        self.assert_contract_owner();
+        self.ft_token_whitelist.insert(&ft_token_id);
+    }
+
+    pub fn remove_ft_token(&mut self, ft_token_id: AccountId) {
+        self.assert_contract_owner();
+        self.ft_token_whitelist.remove(&ft_token_id);
+    }
+
+    pub fn ft_tokens(&self) -> Vec<AccountId> {
+        self.ft_token_whitelist.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+    use near_sdk::testing_env;
+    use near_sdk::{RuntimeFeesConfig, VMConfig};
+
+    #[test]
+    fn test_bounty_paid_on_finish_and_restored_on_failed_transfer() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        register(&mut contract, &accounts(1));
+        set_caller_with_deposit(&mut context, accounts(1), 2 * MINT_STORAGE_COST);
+        contract.create_request("Necesito ayuda con Rust".to_string(), Some(U128(MINT_STORAGE_COST)), None, None, None, None);
+        let request = contract.get_request(0).unwrap();
+        assert_eq!(request.bounty, U128(MINT_STORAGE_COST));
+        assert_eq!(request.escrow, EscrowStatus::Locked);
+
+        apply_to_request(&mut context, &mut contract, accounts(2), 0);
+        set_caller(&mut context, accounts(1));
+        contract.accept_application(0, accounts(2));
+        contract.finish_request(0);
+        assert_eq!(contract.get_request(0).unwrap().escrow, EscrowStatus::Transferring);
+
+        // the transfer failed, the bounty stays in the contract
+        testing_env!(
+            context.predecessor_account_id(accounts(0)).current_account_id(accounts(0)).build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Failed],
+        );
+        assert!(!contract.resolve_bounty_transfer(0));
+        assert_eq!(contract.get_request(0).unwrap().escrow, EscrowStatus::Locked);
+
+        // anyone can retry it
+        testing_env!(context.predecessor_account_id(accounts(3)).build());
+        contract.release_bounty(0);
+        testing_env!(
+            context.predecessor_account_id(accounts(0)).build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Successful(vec![])],
+        );
+        assert!(contract.resolve_bounty_transfer(0));
+        assert_eq!(contract.get_request(0).unwrap().escrow, EscrowStatus::Paid);
+    }
+
+    #[test]
+    fn test_ft_on_transfer_creates_requests_paid_from_the_storage_balance() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        contract.add_ft_token(accounts(4));
+        register(&mut contract, &accounts(1));
+        let create_msg = r#"{"action": "create", "description": "Necesito ayuda con Rust"}"#.to_string();
+
+        // without a storage balance the tokens go back
+        set_caller(&mut context, accounts(4));
+        assert_eq!(unused_tokens(contract.ft_on_transfer(accounts(1), U128(100), create_msg.clone())), U128(100));
+        assert!(contract.get_request(0).is_none());
+
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        let balance = contract.deposit_request_storage();
+        assert!(balance.0 > 0 && balance.0 < MINT_STORAGE_COST);
+
+        set_caller(&mut context, accounts(4));
+        assert_eq!(unused_tokens(contract.ft_on_transfer(accounts(1), U128(100), create_msg.clone())), U128(0));
+        let request = contract.get_request(0).unwrap();
+        assert_eq!(request.owner_id, accounts(1));
+        assert_eq!(request.ft_bounty, U128(100));
+        assert_eq!(request.ft_escrow, EscrowStatus::Locked);
+        assert!(request.storage_deposit.0 > 0);
+        assert_eq!(contract.request_storage_balance(accounts(1)).0, balance.0 - request.storage_deposit.0);
+
+        // accounts without a profile can't create requests
+        assert_eq!(unused_tokens(contract.ft_on_transfer(accounts(2), U128(100), create_msg)), U128(100));
+
+        set_caller_with_deposit(&mut context, accounts(1), 1);
+        assert!(contract.withdraw_request_storage().0 > balance.0 - request.storage_deposit.0);
+        assert_eq!(contract.request_storage_balance(accounts(1)), U128(0));
+    }
+
+    #[test]
+    fn test_ft_on_transfer_funds_requests_from_whitelisted_tokens() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        contract.add_ft_token(accounts(4));
+        create_request_as(&mut context, &mut contract, accounts(1), "Necesito ayuda con Rust");
+
+        // accounts(4) is the token contract calling back after ft_transfer_call
+        set_caller(&mut context, accounts(4));
+        let fund_msg = r#"{"action": "fund", "request_id": 0}"#.to_string();
+        assert_eq!(unused_tokens(contract.ft_on_transfer(accounts(1), U128(100), fund_msg.clone())), U128(0));
+        let request = contract.get_request(0).unwrap();
+        assert_eq!(request.ft_token_id, Some(accounts(4)));
+        assert_eq!(request.ft_escrow, EscrowStatus::Locked);
+
+        contract.ft_on_transfer(accounts(1), U128(50), fund_msg.clone());
+        assert_eq!(contract.get_request(0).unwrap().ft_bounty, U128(150));
+
+        // only the owner funds the request, anyone else gets the tokens back
+        assert_eq!(unused_tokens(contract.ft_on_transfer(accounts(2), U128(50), fund_msg.clone())), U128(50));
+
+        // tokens that are not whitelisted are returned
+        set_caller(&mut context, accounts(5));
+        assert_eq!(unused_tokens(contract.ft_on_transfer(accounts(1), U128(50), fund_msg)), U128(50));
+        assert_eq!(contract.get_request(0).unwrap().ft_bounty, U128(150));
+    }
+}
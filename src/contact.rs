@@ -0,0 +1,106 @@
+use crate::*;
+
+//max length of the encrypted contact details of a share
+pub const MAX_CONTACT_LEN: usize = 512;
+
+//contact details an account shared with a counterpart of a request, encrypted by the client for that counterpart
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContactShare {
+    pub author_id: AccountId,
+    pub recipient_id: AccountId,
+    pub contact: String,
+    //storage deposit paid by the author, refunded when the share is replaced or the request is deleted
+    pub deposit: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    /*
+        The owner of a request in progress or complete shares contact details with one of its helpers, and each
+        helper shares theirs with the owner. The contract state is public, so the client encrypts the details
+        with the key of the recipient before sending them. Sharing again replaces the previous details.
+    */
+    #[payable]
+    pub fn share_contact(&mut self, request_id: u64, recipient_id: AccountId, contact: String) -> ContactShare {
+        let request = self.requests.get(&request_id)
+            .unwrap_or_else(|| env::panic_str("No existe la solicitud"));
+
+        if request.status != RequestStatus::InProgress && request.status != RequestStatus::Complete {
+            env::panic_str("Los datos de contacto solo se comparten en solicitudes en progreso o finalizadas");
+        }
+
+        let author_id = env::signer_account_id();
+        let is_helper = |id: &AccountId| request.participants.iter().any(|participant| &participant.account_id == id);
+        let linked = (request.owner_id == author_id && is_helper(&recipient_id))
+            || (request.owner_id == recipient_id && is_helper(&author_id));
+        if !linked {
+            env::panic_str("La solicitud no relaciona a estas cuentas");
+        }
+
+        if contact.is_empty() || contact.len() > MAX_CONTACT_LEN {
+            env::panic_str(&format!("Los datos de contacto deben tener entre 1 y {} bytes", MAX_CONTACT_LEN));
+        }
+
+        self.internal_add_contact_share(&request_id, author_id, recipient_id, contact)
+    }
+
+    //encrypted contact details the author shared with the recipient in the request, only the recipient can read them
+    pub fn get_contact(&self, request_id: u64, author_id: AccountId, recipient_id: AccountId) -> Option<String> {
+        self.contacts_per_request.get(&request_id)?
+            .get(&(author_id, recipient_id))
+            .map(|share| share.contact)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn test_contact_shared_with_counterparts() {
+        let mut context = get_context(accounts(0));
+        let mut contract = contract_with_attended_request(&mut context);
+
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        let share = contract.share_contact(0, accounts(2), "cifrado-para-2".to_string());
+        assert!(share.deposit.0 > 0);
+        set_caller_with_deposit(&mut context, accounts(2), MINT_STORAGE_COST);
+        contract.share_contact(0, accounts(1), "cifrado-para-1".to_string());
+        testing_env!(context.attached_deposit(0).build());
+
+        assert_eq!(contract.get_contact(0, accounts(1), accounts(2)), Some("cifrado-para-2".to_string()));
+        assert_eq!(contract.get_contact(0, accounts(2), accounts(1)), Some("cifrado-para-1".to_string()));
+        assert_eq!(contract.get_contact(0, accounts(3), accounts(1)), None);
+
+        // sharing again replaces the details
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        contract.share_contact(0, accounts(2), "nuevo-cifrado".to_string());
+        assert_eq!(contract.get_contact(0, accounts(1), accounts(2)), Some("nuevo-cifrado".to_string()));
+    }
+
+    #[test]
+    fn test_contact_not_shared_outside_a_linked_request() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        create_request_as(&mut context, &mut contract, accounts(1), "Necesito ayuda con Rust");
+        apply_to_request(&mut context, &mut contract, accounts(2), 0);
+
+        // nobody was accepted yet
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        assert_contract_panics(module_path!(), "test_contact_not_shared_outside_a_linked_request", "solo se comparten", || {
+            contract.share_contact(0, accounts(2), "cifrado".to_string());
+        });
+
+        set_caller(&mut context, accounts(1));
+        contract.accept_application(0, accounts(2));
+
+        // accounts(3) is not part of the request
+        set_caller_with_deposit(&mut context, accounts(3), MINT_STORAGE_COST);
+        assert_contract_panics(module_path!(), "test_contact_not_shared_outside_a_linked_request", "no relaciona", || {
+            contract.share_contact(0, accounts(1), "cifrado".to_string());
+        });
+    }
+}
@@ -0,0 +1,257 @@
+use crate::*;
+
+//max length (in characters) of the evidence attached to a dispute
+pub const MAX_EVIDENCE_LEN: usize = 1000;
+
+//how an arbiter settled a dispute
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum DisputeResolution {
+    //the request is completed and the helper gets the rewards
+    Helper,
+    //the helper is removed and the request goes back to Open
+    OwnerReopen,
+    //the request is cancelled and the bounties go back to the owner
+    OwnerCancel,
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Dispute {
+    pub opened_by: AccountId,
+    pub evidence: String,
+    pub opened_at: U64,
+    pub resolution: Option<DisputeResolution>,
+    pub resolved_by: Option<AccountId>,
+}
+
+#[near_bindgen]
+impl Contract {
+    //the owner or a helper escalate an in progress request to the arbiters
+    pub fn open_dispute(&mut self, request_id: u64, evidence: String) -> Option<Request> {
+        if let Some(request_data) = self.requests.get(&request_id) {
+            let opened_by = env::signer_account_id();
+            let helper_ids: Vec<AccountId> = request_data.participants.iter()
+                .filter(|participant| !participant.completed)
+                .map(|participant| participant.account_id.clone())
+                .collect();
+
+            if request_data.owner_id != opened_by && !helper_ids.contains(&opened_by) {
+                env::panic_str("Solo el creador y quien atiende la solicitud pueden disputarla");
+            }
+
+            if request_data.status != RequestStatus::InProgress {
+                env::panic_str("Solo se pueden disputar solicitudes en progreso");
+            }
+
+            self.assert_request_not_frozen(&request_data);
+
+            if evidence.chars().count() > MAX_EVIDENCE_LEN {
+                env::panic_str(&format!("La evidencia no puede superar {} caracteres", MAX_EVIDENCE_LEN));
+            }
+
+            let new_request = Request {
+                status: RequestStatus::Disputed,
+                dispute: Some(Dispute {
+                    opened_by: opened_by.clone(),
+                    evidence: evidence.clone(),
+                    opened_at: U64(env::block_timestamp()),
+                    resolution: None,
+                    resolved_by: None,
+                }),
+                ..request_data
+            };
+
+            self.requests.insert(&request_id, &new_request);
+            self.internal_update_status_index(&new_request, Some(&RequestStatus::InProgress));
+
+            //both parties take part in the dispute
+            self.internal_update_reputation(&new_request.owner_id, |reputation| reputation.disputes += 1);
+            for helper_id in helper_ids.iter() {
+                self.internal_update_reputation(helper_id, |reputation| reputation.disputes += 1);
+            }
+
+            let dispute_log: EventLog = EventLog {
+                standard: SWAPTI_STANDARD_NAME.to_string(),
+                version: SWAPTI_EVENT_SPEC.to_string(),
+                event: EventLogVariant::DisputeOpened(vec![DisputeOpenedLog {
+                    request_id,
+                    opened_by: opened_by.to_string(),
+                    evidence,
+                }]),
+            };
+            env::log_str(&dispute_log.to_string());
+
+            Some(new_request)
+        } else {
+            env::panic_str("No existe la solicitud a disputar");
+        }
+    }
+
+    //an arbiter who is not a party of the request settles the dispute in favor of one of them
+    pub fn resolve_dispute(&mut self, request_id: u64, resolution: DisputeResolution) -> Option<Request> {
+        let arbiter_id = env::predecessor_account_id();
+        if !self.arbiters.contains(&arbiter_id) {
+            env::panic_str("Solo un árbitro puede resolver disputas");
+        }
+
+        if let Some(mut request_data) = self.requests.get(&request_id) {
+            if request_data.status != RequestStatus::Disputed {
+                env::panic_str("La solicitud no está en disputa");
+            }
+
+            //an arbiter can't settle a dispute they are a party of
+            if request_data.owner_id == arbiter_id
+                || request_data.participants.iter().any(|participant| participant.account_id == arbiter_id)
+            {
+                env::panic_str("Un árbitro no puede resolver una disputa en la que participa");
+            }
+
+            if let Some(dispute) = request_data.dispute.as_mut() {
+                dispute.resolution = Some(resolution.clone());
+                dispute.resolved_by = Some(arbiter_id.clone());
+            }
+
+            let new_request = match resolution {
+                DisputeResolution::Helper => self.internal_complete_request(request_data),
+                DisputeResolution::OwnerReopen => self.internal_reopen_request(request_data),
+                DisputeResolution::OwnerCancel => {
+                    for participant in request_data.participants.iter().filter(|participant| !participant.completed) {
+                        self.internal_update_reputation(&participant.account_id, |reputation| reputation.abandoned += 1);
+                    }
+
+                    let mut new_request = Request {
+                        status: RequestStatus::Cancelled,
+                        ..request_data
+                    };
+                    self.requests.insert(&request_id, &new_request);
+                    self.internal_update_status_index(&new_request, Some(&RequestStatus::Disputed));
+
+                    if new_request.escrow == EscrowStatus::Locked {
+                        self.internal_release_bounty(&mut new_request);
+                    }
+                    if new_request.ft_escrow == EscrowStatus::Locked {
+                        self.internal_release_ft_bounty(&mut new_request);
+                    }
+                    new_request
+                }
+            };
+
+            let dispute_log: EventLog = EventLog {
+                standard: SWAPTI_STANDARD_NAME.to_string(),
+                version: SWAPTI_EVENT_SPEC.to_string(),
+                event: EventLogVariant::DisputeResolved(vec![DisputeResolvedLog {
+                    request_id,
+                    arbiter_id: arbiter_id.to_string(),
+                    resolution: format!("{:?}", resolution),
+                }]),
+            };
+            env::log_str(&dispute_log.to_string());
+
+            Some(new_request)
+        } else {
+            env::panic_str("No existe la solicitud");
+        }
+    }
+
+    //the contract owner manages who can resolve disputes
+    pub fn add_arbiter(&mut self, account_id: AccountId) {
+        self.assert_contract_owner();
+        self.arbiters.insert(&account_id);
+    }
+
+    pub fn remove_arbiter(&mut self, account_id: AccountId) {
+        self.assert_contract_owner();
+        self.arbiters.remove(&account_id);
+    }
+
+    pub fn get_arbiters(&self) -> Vec<AccountId> {
+        self.arbiters.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+
+    #[test]
+    fn test_arbiter_resolves_dispute_for_helper() {
+        let mut context = get_context(accounts(0));
+        let mut contract = contract_with_attended_request(&mut context);
+
+        // the helper says the work is done but the owner does not finish the request
+        set_caller(&mut context, accounts(2));
+        contract.open_dispute(0, "Entregué la solución y no hay respuesta".to_string());
+        let request = contract.get_request(0).unwrap();
+        assert!(request.status == RequestStatus::Disputed);
+        assert_eq!(request.dispute.unwrap().opened_by, accounts(2));
+        assert_eq!(contract.get_reputation(accounts(1)).disputes, 1);
+        assert_eq!(contract.get_reputation(accounts(2)).disputes, 1);
+
+        set_caller(&mut context, accounts(0));
+        contract.add_arbiter(accounts(4));
+        set_caller(&mut context, accounts(4));
+        contract.resolve_dispute(0, DisputeResolution::Helper);
+
+        let request = contract.get_request(0).unwrap();
+        assert!(request.status == RequestStatus::Complete);
+        assert!(request.dispute.unwrap().resolution == Some(DisputeResolution::Helper));
+        assert_eq!(contract.get_reputation(accounts(2)).requests_completed, 1);
+    }
+
+    #[test]
+    fn test_arbiter_reopens_or_cancels_for_owner() {
+        let mut context = get_context(accounts(0));
+        let mut contract = contract_with_attended_request(&mut context);
+        create_request_as(&mut context, &mut contract, accounts(1), "Necesito ayuda con NEAR");
+        apply_to_request(&mut context, &mut contract, accounts(3), 1);
+        set_caller(&mut context, accounts(1));
+        contract.accept_application(1, accounts(3));
+        contract.open_dispute(0, "No hubo avances".to_string());
+        contract.open_dispute(1, "No responde".to_string());
+
+        set_caller(&mut context, accounts(0));
+        contract.add_arbiter(accounts(4));
+        set_caller(&mut context, accounts(4));
+        contract.resolve_dispute(0, DisputeResolution::OwnerReopen);
+        let request = contract.get_request(0).unwrap();
+        assert!(request.status == RequestStatus::Open);
+        assert!(request.helper.is_none());
+        assert!(request.participants.is_empty());
+        assert_eq!(contract.get_reputation(accounts(2)).abandoned, 1);
+
+        contract.resolve_dispute(1, DisputeResolution::OwnerCancel);
+        let request = contract.get_request(1).unwrap();
+        assert!(request.status == RequestStatus::Cancelled);
+        assert!(request.dispute.unwrap().resolved_by == Some(accounts(4)));
+        assert_eq!(contract.get_reputation(accounts(3)).abandoned, 1);
+    }
+
+    #[test]
+    fn test_only_outside_arbiters_resolve_disputes() {
+        let mut context = get_context(accounts(0));
+        let mut contract = contract_with_attended_request(&mut context);
+        set_caller(&mut context, accounts(2));
+        contract.open_dispute(0, "Entregué la solución y no hay respuesta".to_string());
+
+        set_caller(&mut context, accounts(3));
+        assert_contract_panics(module_path!(), "test_only_outside_arbiters_resolve_disputes", "Solo un árbitro", || {
+            contract.resolve_dispute(0, DisputeResolution::Helper);
+        });
+
+        // the helper and the owner can't settle their own dispute even as arbiters
+        set_caller(&mut context, accounts(0));
+        contract.add_arbiter(accounts(1));
+        contract.add_arbiter(accounts(2));
+        set_caller(&mut context, accounts(2));
+        assert_contract_panics_case(module_path!(), "test_only_outside_arbiters_resolve_disputes", "helper as arbiter", "en la que participa", || {
+            contract.resolve_dispute(0, DisputeResolution::Helper);
+        });
+        set_caller(&mut context, accounts(1));
+        assert_contract_panics_case(module_path!(), "test_only_outside_arbiters_resolve_disputes", "owner as arbiter", "en la que participa", || {
+            contract.resolve_dispute(0, DisputeResolution::OwnerCancel);
+        });
+    }
+}
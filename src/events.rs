@@ -3,7 +3,7 @@ use std::fmt;
 use near_sdk::serde::{Deserialize, Serialize};
 
 /// Enum that represents the data type of the EventLog.
-/// The enum can either be an NftMint or an NftTransfer.
+/// The enum can either be an NFT event (nep171) or a request marketplace event (swapti).
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "event", content = "data")]
 #[serde(rename_all = "snake_case")]
@@ -12,6 +12,15 @@ use near_sdk::serde::{Deserialize, Serialize};
 pub enum EventLogVariant {
     NftMint(Vec<NftMintLog>),
     NftTransfer(Vec<NftTransferLog>),
+    ReviewSubmitted(Vec<ReviewSubmittedLog>),
+    ReviewRevealed(Vec<ReviewRevealedLog>),
+    DisputeOpened(Vec<DisputeOpenedLog>),
+    DisputeResolved(Vec<DisputeResolvedLog>),
+    RequestExpired(Vec<RequestExpiredLog>),
+    RequestUpdated(Vec<RequestUpdatedLog>),
+    ProfileCreated(Vec<ProfileLog>),
+    ProfileUpdated(Vec<ProfileLog>),
+    ProfileDeleted(Vec<ProfileLog>),
 }
 
 /// Interface to capture data about an event
@@ -78,6 +87,102 @@ pub struct NftTransferLog {
     pub memo: Option<String>,
 }
 
+/// An event log to capture that a review was submitted. The content stays hidden until revealed
+///
+/// Arguments
+/// * `request_id`: 0
+/// * `reviewer_id`: "owner.near"
+/// * `reviewee_id`: "helper.near"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReviewSubmittedLog {
+    pub request_id: u64,
+    pub reviewer_id: String,
+    pub reviewee_id: String,
+}
+
+/// An event log to capture a review once it becomes public
+///
+/// Arguments
+/// * `request_id`: 0
+/// * `reviewer_id`: "owner.near"
+/// * `reviewee_id`: "helper.near"
+/// * `rating`: 1 to 5
+/// * `comment`: "Muy buena explicación"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReviewRevealedLog {
+    pub request_id: u64,
+    pub reviewer_id: String,
+    pub reviewee_id: String,
+    pub rating: u8,
+    pub comment: String,
+}
+
+/// An event log to capture a request entering a dispute
+///
+/// Arguments
+/// * `request_id`: 0
+/// * `opened_by`: "helper.near"
+/// * `evidence`: "El creador no responde desde hace una semana"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DisputeOpenedLog {
+    pub request_id: u64,
+    pub opened_by: String,
+    pub evidence: String,
+}
+
+/// An event log to capture an arbiter settling a dispute
+///
+/// Arguments
+/// * `request_id`: 0
+/// * `arbiter_id`: "arbiter.near"
+/// * `resolution`: "Helper", "OwnerReopen" or "OwnerCancel"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DisputeResolvedLog {
+    pub request_id: u64,
+    pub arbiter_id: String,
+    pub resolution: String,
+}
+
+/// An event log to capture a timed out request being cleaned up
+///
+/// Arguments
+/// * `request_id`: 0
+/// * `status`: "Open" or "Expired"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RequestExpiredLog {
+    pub request_id: u64,
+    pub status: String,
+}
+
+/// An event log to capture the owner editing an open request
+///
+/// Arguments
+/// * `request_id`: 0
+/// * `revision`: number of previous descriptions kept for the request
+/// * `description`: "Necesito ayuda para aprender JS y React"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RequestUpdatedLog {
+    pub request_id: u64,
+    pub revision: u64,
+    pub description: String,
+}
+
+/// An event log to capture a profile being created, updated or deleted
+///
+/// Arguments
+/// * `account_id`: "user.near"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProfileLog {
+    pub account_id: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,4 +240,19 @@ mod tests {
         };
         assert_eq!(expected, log.to_string());
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn swapti_format_review_submitted() {
+        let expected = r#"EVENT_JSON:{"standard":"swapti","version":"1.0.0","event":"review_submitted","data":[{"request_id":0,"reviewer_id":"user1.near","reviewee_id":"user2.near"}]}"#;
+        let log = EventLog {
+            standard: "swapti".to_string(),
+            version: "1.0.0".to_string(),
+            event: EventLogVariant::ReviewSubmitted(vec![ReviewSubmittedLog {
+                request_id: 0,
+                reviewer_id: "user1.near".to_string(),
+                reviewee_id: "user2.near".to_string(),
+            }]),
+        };
+        assert_eq!(expected, log.to_string());
+    }
+}
\ No newline at end of file
@@ -38,10 +38,15 @@ pub(crate) fn refund_approved_account_ids(
 
 //used to generate a unique prefix in our storage collections (this is to avoid data collisions)
 pub(crate) fn hash_account_id(account_id: &AccountId) -> CryptoHash {
+    hash_str(account_id.as_str())
+}
+
+//used to generate a unique prefix in our storage collections from a string, such as a tag or a skill
+pub(crate) fn hash_str(value: &str) -> CryptoHash {
     //get the default hash
     let mut hash = CryptoHash::default();
-    //we hash the account ID and return it
-    hash.copy_from_slice(&env::sha256(account_id.as_bytes()));
+    //we hash the string and return it
+    hash.copy_from_slice(&env::sha256(value.as_bytes()));
     hash
 }
 
@@ -86,6 +91,231 @@ pub(crate) fn refund_deposit(storage_used: u64) {
 }
 
 impl Contract {
+    //make sure the caller is the owner of the contract
+    pub(crate) fn assert_contract_owner(&self) {
+        if env::predecessor_account_id() != self.owner_id {
+            env::panic_str("Solo el dueño del contrato puede hacer esto");
+        }
+    }
+
+    //build an open request with the next request ID, without storing it
+    pub(crate) fn internal_new_request(&self, owner_id: &AccountId, description: String) -> Request {
+        Request {
+            request_id: self.next_request_id,
+            owner_id: owner_id.clone(),
+            description,
+            helper: None,
+            status: RequestStatus::Open,
+            attended_at: None,
+            completed_at: None,
+            bounty: U128(0),
+            escrow: EscrowStatus::NoBounty,
+            ft_token_id: None,
+            ft_bounty: U128(0),
+            ft_escrow: EscrowStatus::NoBounty,
+            dispute: None,
+            deadline: None,
+            work_timeout: U64(DEFAULT_WORK_TIMEOUT),
+            created_at: U64(env::block_timestamp()),
+            tags: vec![],
+            storage_deposit: U128(0),
+            capacity: 1,
+            participants: vec![],
+        }
+    }
+
+    //open and in progress requests take new helpers while they have free seats
+    pub(crate) fn internal_accepts_helpers(&self, request: &Request) -> bool {
+        match request.status {
+            RequestStatus::Open | RequestStatus::InProgress => (request.participants.len() as u64) < request.capacity,
+            _ => false,
+        }
+    }
+
+    //validates the tags chosen by the owner against the registry, duplicates are dropped
+    pub(crate) fn internal_set_tags(&self, request: &mut Request, tags: Vec<String>) {
+        let mut request_tags: Vec<String> = vec![];
+        for tag in tags {
+            if !self.tag_registry.contains(&tag) {
+                env::panic_str(&format!("La etiqueta {} no existe", tag));
+            }
+            if !request_tags.contains(&tag) {
+                request_tags.push(tag);
+            }
+        }
+
+        if request_tags.len() > MAX_TAGS_PER_REQUEST {
+            env::panic_str(&format!("Una solicitud puede tener hasta {} etiquetas", MAX_TAGS_PER_REQUEST));
+        }
+
+        request.tags = request_tags;
+    }
+
+    //validates and stores the deadline and the work timeout chosen by the owner
+    pub(crate) fn internal_set_deadlines(&self, request: &mut Request, deadline: Option<U64>, work_timeout: Option<U64>) {
+        if let Some(deadline) = deadline {
+            if deadline.0 <= env::block_timestamp() {
+                env::panic_str("La fecha límite debe ser posterior al momento actual");
+            }
+            request.deadline = Some(deadline);
+        }
+
+        if let Some(work_timeout) = work_timeout {
+            if work_timeout.0 == 0 {
+                env::panic_str("El tiempo para atender la solicitud debe ser mayor a cero");
+            }
+            request.work_timeout = work_timeout;
+        }
+    }
+
+    pub(crate) fn internal_deadline_passed(&self, request: &Request) -> bool {
+        request.deadline.is_some_and(|deadline| env::block_timestamp() >= deadline.0)
+    }
+
+    //store a new request and index it for its owner
+    pub(crate) fn internal_add_request(&mut self, request: &Request) {
+        self.next_request_id += 1;
+        self.requests.insert(&request.request_id, request);
+        self.internal_add_request_to_owner(&request.owner_id, &request.request_id);
+        self.internal_update_status_index(request, None);
+        for tag in request.tags.iter() {
+            self.internal_add_request_to_tag(tag, &request.request_id);
+        }
+    }
+
+    //removes a request and every index entry pointing to it
+    pub(crate) fn internal_remove_request(&mut self, request: &Request) {
+        let request_id = request.request_id;
+
+        self.requests.remove(&request_id);
+        self.internal_clear_applications(&request_id);
+        self.internal_clear_contact_shares(&request_id);
+
+        //both threads are capped, so clearing them takes bounded gas
+        if let Some(mut revisions) = self.revisions_per_request.remove(&request_id) {
+            revisions.clear();
+        }
+        if let Some(mut messages) = self.messages_per_request.remove(&request_id) {
+            //every author gets back the storage of their messages
+            let mut refunds: HashMap<AccountId, Balance> = HashMap::new();
+            let mut storage_usage = env::storage_usage();
+            while let Some(message) = messages.pop() {
+                let freed_storage_in_bytes = storage_usage - env::storage_usage();
+                storage_usage = env::storage_usage();
+                *refunds.entry(message.author_id).or_default() += env::storage_byte_cost() * Balance::from(freed_storage_in_bytes);
+            }
+            for (author_id, refund) in refunds {
+                Promise::new(author_id).transfer(refund);
+            }
+        }
+        self.internal_remove_request_from_owner(&request.owner_id, &request_id);
+
+        for participant in request.participants.iter() {
+            self.internal_remove_request_from_helper(&participant.account_id, &request_id);
+        }
+
+        if let Some(mut status_index) = self.requests_per_status.get(&request.status) {
+            status_index.remove(&request_id);
+            self.requests_per_status.insert(&request.status, &status_index);
+        }
+
+        for tag in request.tags.iter() {
+            if let Some(mut request_set) = self.requests_per_tag.get(tag) {
+                request_set.remove(&request_id);
+                if request_set.is_empty() {
+                    self.requests_per_tag.remove(tag);
+                } else {
+                    self.requests_per_tag.insert(tag, &request_set);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn internal_add_request_to_tag(&mut self, tag: &String, request_id: &u64) {
+        let mut request_set = self.requests_per_tag.get(tag).unwrap_or_else(|| {
+            UnorderedSet::new(
+                StorageKey::RequestsPerTagInner {
+                    tag_hash: hash_str(tag),
+                }
+                .try_to_vec()
+                .unwrap(),
+            )
+        });
+
+        request_set.insert(request_id);
+
+        self.requests_per_tag.insert(tag, &request_set);
+    }
+
+    //moves a request to the index of its current status, `from` is the status it had before (None for new requests)
+    pub(crate) fn internal_update_status_index(&mut self, request: &Request, from: Option<&RequestStatus>) {
+        if let Some(from) = from {
+            if let Some(mut status_index) = self.requests_per_status.get(from) {
+                status_index.remove(&request.request_id);
+                self.requests_per_status.insert(from, &status_index);
+            }
+        }
+
+        let mut status_index = self.requests_per_status.get(&request.status).unwrap_or_else(|| {
+            TreeMap::new(
+                StorageKey::RequestsPerStatusInner {
+                    status: request.status.clone(),
+                }
+                .try_to_vec()
+                .unwrap(),
+            )
+        });
+        status_index.insert(&request.request_id, &request.created_at.0);
+        self.requests_per_status.insert(&request.status, &status_index);
+    }
+
+    //stores a new token for the receiver and logs the mint, the caller charges the storage
+    pub(crate) fn internal_mint(&mut self, token_id: &TokenId, metadata: &TokenMetadata, receiver_id: &AccountId) {
+        //specify the token struct that contains the owner ID 
+        let token = Token {
+            //set the owner ID equal to the receiver ID passed into the function
+            owner_id: receiver_id.clone(),
+            //we set the approved account IDs to the default value (an empty map)
+            approved_account_ids: Default::default(),
+            //the next approval ID is set to 0
+            next_approval_id: 0,
+            //badges don't pay royalties
+            royalty: HashMap::new(),
+        };
+
+        //insert the token ID and token struct and make sure that the token doesn't exist
+        assert!(
+            self.tokens_by_id.insert(token_id, &token).is_none(),
+            "Token already exists"
+        );
+
+        //insert the token ID and metadata
+        self.token_metadata_by_id.insert(token_id, metadata);
+
+        //call the internal method for adding the token to the owner
+        self.internal_add_token_to_owner(receiver_id, token_id);
+
+        // Construct the mint log as per the events standard.
+        let nft_mint_log: EventLog = EventLog {
+            // Standard name ("nep171").
+            standard: NFT_STANDARD_NAME.to_string(),
+            // Version of the standard ("nft-1.0.0").
+            version: NFT_METADATA_SPEC.to_string(),
+            // The data related with the event stored in a vector.
+            event: EventLogVariant::NftMint(vec![NftMintLog {
+                // Owner of the token.
+                owner_id: receiver_id.to_string(),
+                // Vector of token IDs that were minted.
+                token_ids: vec![token_id.to_string()],
+                // An optional memo to include.
+                memo: None,
+            }]),
+        };
+
+        // Log the serialized json.
+        env::log_str(&nft_mint_log.to_string());
+    }
+
     //add a token to the set of tokens an owner has
     pub(crate) fn internal_add_token_to_owner(
         &mut self,
@@ -98,7 +328,7 @@ impl Contract {
             UnorderedSet::new(
                 StorageKey::TokenPerOwnerInner {
                     //we get a new unique prefix for the collection
-                    account_id_hash: hash_account_id(&account_id),
+                    account_id_hash: hash_account_id(account_id),
                 }
                 .try_to_vec()
                 .unwrap(),
@@ -122,8 +352,8 @@ impl Contract {
         let mut request_set = self.requests_per_owner.get(account_id).unwrap_or_else(|| {
             //if the account doesn't have any tokens, we create a new unordered set
             UnorderedSet::new(
-                StorageKey::TokenPerOwnerInner {
-                    account_id_hash: hash_account_id(&account_id),
+                StorageKey::RequestsPerOwnerInner {
+                    account_id_hash: hash_account_id(account_id),
                 }
                 .try_to_vec()
                 .unwrap(),
@@ -135,6 +365,731 @@ impl Contract {
         self.requests_per_owner.insert(account_id, &request_set);
     }
 
+    //adds a request to the index of the account currently attending it
+    pub(crate) fn internal_add_request_to_helper(
+        &mut self,
+        helper_id: &AccountId,
+        request_id: &u64,
+    ) {
+        let mut request_set = self.requests_per_helper.get(helper_id).unwrap_or_else(|| {
+            UnorderedSet::new(
+                StorageKey::RequestsPerHelperInner {
+                    account_id_hash: hash_account_id(helper_id),
+                }
+                .try_to_vec()
+                .unwrap(),
+            )
+        });
+
+        request_set.insert(request_id);
+
+        self.requests_per_helper.insert(helper_id, &request_set);
+    }
+
+    //removes a request from the index of a helper that no longer attends it
+    pub(crate) fn internal_remove_request_from_helper(
+        &mut self,
+        helper_id: &AccountId,
+        request_id: &u64,
+    ) {
+        if let Some(mut request_set) = self.requests_per_helper.get(helper_id) {
+            request_set.remove(request_id);
+
+            if request_set.is_empty() {
+                self.requests_per_helper.remove(helper_id);
+            } else {
+                self.requests_per_helper.insert(helper_id, &request_set);
+            }
+        }
+    }
+
+    pub(crate) fn internal_remove_request_from_owner(
+        &mut self,
+        account_id: &AccountId,
+        request_id: &u64,
+    ) {
+        if let Some(mut request_set) = self.requests_per_owner.get(account_id) {
+            request_set.remove(request_id);
+
+            if request_set.is_empty() {
+                self.requests_per_owner.remove(account_id);
+            } else {
+                self.requests_per_owner.insert(account_id, &request_set);
+            }
+        }
+    }
+
+    //count a completed request towards the helper's badges. Each request is only counted once,
+    //returns false if the helper was already credited for the request
+    pub(crate) fn internal_credit_helper(
+        &mut self,
+        helper_id: &AccountId,
+        request_id: &u64,
+    ) -> bool {
+        let mut helped_set = self.helped_requests_per_helper.get(helper_id).unwrap_or_else(|| {
+            UnorderedSet::new(
+                StorageKey::HelpedRequestsPerHelperInner {
+                    account_id_hash: hash_account_id(helper_id),
+                }
+                .try_to_vec()
+                .unwrap(),
+            )
+        });
+
+        //insert returns false if the request was already counted
+        if !helped_set.insert(request_id) {
+            return false;
+        }
+        self.helped_requests_per_helper.insert(helper_id, &helped_set);
+
+        self.internal_update_times_helped(helper_id, |times_helped| times_helped.number += 1);
+
+        self.internal_update_reputation(helper_id, |reputation| reputation.requests_completed += 1);
+
+        true
+    }
+
+    //credits the helper and sends them the SWAPTI reward, once per request
+    pub(crate) fn internal_reward_helper(&mut self, helper_id: &AccountId, request_id: &u64) {
+        if !self.internal_credit_helper(helper_id, request_id) {
+            return;
+        }
+
+        // Hacer el envió del swapti token
+        ext_nft::ft_transfer(
+            helper_id.clone(),
+            "1000000000000000000000000".to_string(),
+            "dev-1678227171447-27568759288636".to_string().parse::<AccountId>().unwrap(),
+            NO_DEPOSIT,
+            MIN_GAS_FOR_NFT_TRANSFER_CALL
+        );
+    }
+
+    //store an application paying for its storage with the attached deposit
+    pub(crate) fn internal_add_application(
+        &mut self,
+        request_id: &u64,
+        applicant_id: &AccountId,
+        pitch: String,
+    ) -> Application {
+        let initial_storage_usage = env::storage_usage();
+
+        let mut applications = self.applications_per_request.get(request_id).unwrap_or_else(|| {
+            UnorderedMap::new(
+                StorageKey::ApplicationsPerRequestInner { request_id: *request_id }
+                    .try_to_vec()
+                    .unwrap(),
+            )
+        });
+
+        if applications.get(applicant_id).is_some() {
+            env::panic_str("Ya te postulaste a esta solicitud");
+        }
+
+        if applications.len() >= MAX_APPLICATIONS_PER_REQUEST {
+            env::panic_str("La solicitud ya no acepta más postulaciones");
+        }
+
+        let mut application = Application {
+            applicant_id: applicant_id.clone(),
+            pitch,
+            applied_at: U64(env::block_timestamp()),
+            deposit: U128(0),
+        };
+        applications.insert(applicant_id, &application);
+        self.applications_per_request.insert(request_id, &applications);
+
+        //the deposit has a fixed size, so recording it doesn't change the storage used
+        let required_storage_in_bytes = env::storage_usage() - initial_storage_usage;
+        application.deposit = U128(env::storage_byte_cost() * Balance::from(required_storage_in_bytes));
+        applications.insert(applicant_id, &application);
+
+        //refund any excess storage if the user attached too much. Panic if they didn't attach enough to cover the required.
+        refund_deposit(required_storage_in_bytes);
+
+        application
+    }
+
+    //remove an application and refund its storage deposit to the applicant
+    pub(crate) fn internal_remove_application(
+        &mut self,
+        request_id: &u64,
+        applicant_id: &AccountId,
+    ) -> Option<Application> {
+        let mut applications = self.applications_per_request.get(request_id)?;
+        let application = applications.remove(applicant_id)?;
+
+        if applications.is_empty() {
+            self.applications_per_request.remove(request_id);
+        } else {
+            self.applications_per_request.insert(request_id, &applications);
+        }
+
+        if application.deposit.0 > 0 {
+            Promise::new(application.applicant_id.clone()).transfer(application.deposit.0);
+        }
+
+        Some(application)
+    }
+
+    //reject every pending application of a request, refunding their deposits
+    pub(crate) fn internal_clear_applications(&mut self, request_id: &u64) {
+        let mut applications = if let Some(applications) = self.applications_per_request.get(request_id) {
+            applications
+        } else {
+            return;
+        };
+
+        for application in applications.values() {
+            if application.deposit.0 > 0 {
+                Promise::new(application.applicant_id.clone()).transfer(application.deposit.0);
+            }
+        }
+
+        applications.clear();
+        self.applications_per_request.remove(request_id);
+    }
+
+    //store a contact share paying for its storage with the attached deposit, replacing the previous one
+    pub(crate) fn internal_add_contact_share(
+        &mut self,
+        request_id: &u64,
+        author_id: AccountId,
+        recipient_id: AccountId,
+        contact: String,
+    ) -> ContactShare {
+        let mut shares = self.contacts_per_request.get(request_id).unwrap_or_else(|| {
+            UnorderedMap::new(
+                StorageKey::ContactsPerRequestInner { request_id: *request_id }
+                    .try_to_vec()
+                    .unwrap(),
+            )
+        });
+
+        let key = (author_id.clone(), recipient_id.clone());
+        if let Some(previous_share) = shares.remove(&key) {
+            if previous_share.deposit.0 > 0 {
+                Promise::new(author_id.clone()).transfer(previous_share.deposit.0);
+            }
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        let mut share = ContactShare {
+            author_id,
+            recipient_id,
+            contact,
+            deposit: U128(0),
+        };
+        shares.insert(&key, &share);
+        self.contacts_per_request.insert(request_id, &shares);
+
+        //the deposit has a fixed size, so recording it doesn't change the storage used
+        let required_storage_in_bytes = env::storage_usage().saturating_sub(initial_storage_usage);
+        share.deposit = U128(env::storage_byte_cost() * Balance::from(required_storage_in_bytes));
+        shares.insert(&key, &share);
+
+        refund_deposit(required_storage_in_bytes);
+
+        share
+    }
+
+    //drop every contact share of a request, refunding their deposits
+    pub(crate) fn internal_clear_contact_shares(&mut self, request_id: &u64) {
+        let mut shares = if let Some(shares) = self.contacts_per_request.get(request_id) {
+            shares
+        } else {
+            return;
+        };
+
+        for share in shares.values() {
+            if share.deposit.0 > 0 {
+                Promise::new(share.author_id.clone()).transfer(share.deposit.0);
+            }
+        }
+
+        shares.clear();
+        self.contacts_per_request.remove(request_id);
+    }
+
+    //pays the bounty to the helper of a completed request, or refunds it to the owner otherwise
+    pub(crate) fn internal_release_bounty(&mut self, request: &mut Request) -> Promise {
+        let receiver_id = if request.status == RequestStatus::Complete {
+            request.helper.clone().expect("La solicitud no tiene quien la atienda")
+        } else {
+            request.owner_id.clone()
+        };
+
+        request.escrow = EscrowStatus::Transferring;
+        self.requests.insert(&request.request_id, request);
+
+        Promise::new(receiver_id)
+            .transfer(request.bounty.0)
+            .then(ext_bounty::resolve_bounty_transfer(
+                request.request_id,
+                env::current_account_id(),
+                NO_DEPOSIT,
+                GAS_FOR_RESOLVE_BOUNTY_TRANSFER,
+            ))
+    }
+
+    //pays the fungible token bounty to the helper of a completed request, or refunds it to the owner otherwise
+    pub(crate) fn internal_release_ft_bounty(&mut self, request: &mut Request) -> Promise {
+        let receiver_id = if request.status == RequestStatus::Complete {
+            request.helper.clone().expect("La solicitud no tiene quien la atienda")
+        } else {
+            request.owner_id.clone()
+        };
+
+        request.ft_escrow = EscrowStatus::Transferring;
+        self.requests.insert(&request.request_id, request);
+
+        ext_ft::ft_transfer(
+            receiver_id,
+            request.ft_bounty,
+            Some(format!("Recompensa de la solicitud {}", request.request_id)),
+            request.ft_token_id.clone().unwrap(),
+            1,
+            GAS_FOR_FT_TRANSFER,
+        )
+        .then(ext_bounty::resolve_ft_bounty_transfer(
+            request.request_id,
+            env::current_account_id(),
+            NO_DEPOSIT,
+            GAS_FOR_RESOLVE_BOUNTY_TRANSFER,
+        ))
+    }
+
+    //mark a request as Complete, credit the helper and pay the rewards
+    pub(crate) fn internal_complete_request(&mut self, request: Request) -> Request {
+        let previous_status = request.status.clone();
+        let mut new_request = Request {
+            status: RequestStatus::Complete,
+            completed_at: Some(U64(env::block_timestamp())),
+            ..request
+        };
+
+        //every helper that was not signed off yet is completed with the request
+        let mut helpers_to_reward = vec![];
+        for participant in new_request.participants.iter_mut().filter(|participant| !participant.completed) {
+            participant.completed = true;
+            helpers_to_reward.push(participant.account_id.clone());
+        }
+
+        self.requests.insert(&new_request.request_id, &new_request);
+        self.internal_update_status_index(&new_request, Some(&previous_status));
+
+        //credit the helpers in the same call that completes the request
+        for helper_id in helpers_to_reward.iter() {
+            self.internal_reward_helper(helper_id, &new_request.request_id);
+        }
+
+        if new_request.escrow == EscrowStatus::Locked {
+            self.internal_release_bounty(&mut new_request);
+        }
+        if new_request.ft_escrow == EscrowStatus::Locked {
+            self.internal_release_ft_bounty(&mut new_request);
+        }
+
+        new_request
+    }
+
+    /*
+        Removes every helper that was not signed off yet and opens the request again. When the helpers already
+        credited take every seat nobody else can join, so the request is completed instead.
+    */
+    pub(crate) fn internal_reopen_request(&mut self, request: Request) -> Request {
+        let (participants, dropped): (Vec<Participant>, Vec<Participant>) = request.participants
+            .iter()
+            .cloned()
+            .partition(|participant| participant.completed);
+
+        for participant in dropped.iter() {
+            self.internal_update_reputation(&participant.account_id, |reputation| reputation.abandoned += 1);
+            self.internal_remove_request_from_helper(&participant.account_id, &request.request_id);
+        }
+
+        if (participants.len() as u64) >= request.capacity {
+            return self.internal_complete_request(Request { participants, ..request });
+        }
+
+        let previous_status = request.status.clone();
+        let new_request = Request {
+            helper: participants.first().map(|participant| participant.account_id.clone()),
+            participants,
+            status: RequestStatus::Open,
+            attended_at: None,
+            ..request
+        };
+
+        self.requests.insert(&new_request.request_id, &new_request);
+        self.internal_update_status_index(&new_request, Some(&previous_status));
+
+        new_request
+    }
+
+    //drops a helper that was not signed off yet, the request goes back to Open when nobody is left
+    pub(crate) fn internal_remove_participant(&mut self, mut request: Request, helper_id: &AccountId) -> Request {
+        self.internal_update_reputation(helper_id, |reputation| reputation.abandoned += 1);
+        self.internal_remove_request_from_helper(helper_id, &request.request_id);
+
+        request.participants.retain(|participant| &participant.account_id != helper_id);
+        request.helper = request.participants.first().map(|participant| participant.account_id.clone());
+
+        if request.participants.is_empty() {
+            return self.internal_reopen_request(request);
+        }
+
+        self.requests.insert(&request.request_id, &request);
+        request
+    }
+
+    //profiles not moved by migrate_profiles yet are still read from the legacy map
+    pub(crate) fn internal_get_profile(&self, account_id: &AccountId) -> Option<Profile> {
+        self.profiles.get(account_id)
+            .or_else(|| self.legacy_profiles.get(account_id).cloned().map(VersionedProfile::V1))
+            .map(Profile::from)
+    }
+
+    //stores the profile and moves the account between the skill indexes
+    pub(crate) fn internal_set_profile(&mut self, account_id: &AccountId, profile: Profile) {
+        let times_helped = self.internal_get_times_helped(account_id).unwrap_or_default().number;
+        if let Some(previous_profile) = self.profiles.get(account_id).map(Profile::from) {
+            self.internal_remove_from_helped_index(account_id, &previous_profile.skills, times_helped);
+        }
+        if let Some(previous_profile) = self.internal_get_profile(account_id) {
+            for skill in previous_profile.skills.iter().filter(|skill| !profile.skills.contains(skill)) {
+                self.internal_remove_account_from_skill(skill, account_id);
+            }
+        }
+        for skill in profile.skills.iter() {
+            self.internal_add_account_to_skill(skill, account_id);
+        }
+        self.internal_add_to_helped_index(account_id, &profile.skills, times_helped);
+
+        self.legacy_profiles.remove(account_id);
+        self.profiles.insert(account_id, &VersionedProfile::V2(profile));
+    }
+
+    //indexes the profile in the directory and in each of its skills by the number of people the account helped
+    pub(crate) fn internal_add_to_helped_index(&mut self, account_id: &AccountId, skills: &[String], times_helped: u64) {
+        let key = (times_helped, account_id.clone());
+        self.profiles_by_helped.insert(&key, &());
+
+        for skill in skills.iter() {
+            let mut skill_index = self.profiles_by_helped_per_skill.get(skill).unwrap_or_else(|| {
+                TreeMap::new(
+                    StorageKey::ProfilesByHelpedPerSkillInner {
+                        skill_hash: hash_str(skill),
+                    }
+                    .try_to_vec()
+                    .unwrap(),
+                )
+            });
+            skill_index.insert(&key, &());
+            self.profiles_by_helped_per_skill.insert(skill, &skill_index);
+        }
+    }
+
+    pub(crate) fn internal_remove_from_helped_index(&mut self, account_id: &AccountId, skills: &[String], times_helped: u64) {
+        let key = (times_helped, account_id.clone());
+        self.profiles_by_helped.remove(&key);
+
+        for skill in skills.iter() {
+            if let Some(mut skill_index) = self.profiles_by_helped_per_skill.get(skill) {
+                skill_index.remove(&key);
+
+                if skill_index.is_empty() {
+                    self.profiles_by_helped_per_skill.remove(skill);
+                } else {
+                    self.profiles_by_helped_per_skill.insert(skill, &skill_index);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn internal_add_account_to_skill(&mut self, skill: &String, account_id: &AccountId) {
+        let mut account_set = self.accounts_per_skill.get(skill).unwrap_or_else(|| {
+            UnorderedSet::new(
+                StorageKey::AccountsPerSkillInner {
+                    skill_hash: hash_str(skill),
+                }
+                .try_to_vec()
+                .unwrap(),
+            )
+        });
+
+        account_set.insert(account_id);
+
+        self.accounts_per_skill.insert(skill, &account_set);
+    }
+
+    pub(crate) fn internal_remove_account_from_skill(&mut self, skill: &String, account_id: &AccountId) {
+        if let Some(mut account_set) = self.accounts_per_skill.get(skill) {
+            account_set.remove(account_id);
+
+            if account_set.is_empty() {
+                self.accounts_per_skill.remove(skill);
+            } else {
+                self.accounts_per_skill.insert(skill, &account_set);
+            }
+        }
+    }
+
+    //builds a page of the directory, only the accounts in the page are read
+    pub(crate) fn internal_profiles_page<I: Iterator<Item = AccountId>>(
+        &self,
+        account_ids: I,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonProfile> {
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        account_ids
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .map(|account_id| self.internal_json_profile(account_id))
+            .collect()
+    }
+
+    //the stored profile along with the help counter and the pinned token
+    pub(crate) fn internal_json_profile(&self, account_id: AccountId) -> JsonProfile {
+        let avatar = self.avatars.get(&account_id).map(|token_id| Avatar {
+            media: self.token_metadata_by_id.get(&token_id).and_then(|metadata| metadata.media),
+            token_id,
+        });
+
+        JsonProfile {
+            profile: self.internal_get_profile(&account_id).unwrap(),
+            times_helped: self.internal_get_times_helped(&account_id).unwrap_or_default().number,
+            avatar,
+            account_id,
+        }
+    }
+
+    /*
+        Charges the storage a profile change took from the attached deposit, or gives back the freed
+        storage along with the deposit. Only what the account paid can be given back.
+    */
+    pub(crate) fn internal_settle_profile_storage(&mut self, account_id: &AccountId, initial_storage_usage: u64) {
+        let final_storage_usage = env::storage_usage();
+
+        let mut storage_deposit = self.profile_storage_deposits.get(account_id).unwrap_or(0);
+        if final_storage_usage > initial_storage_usage {
+            let required_storage_in_bytes = final_storage_usage - initial_storage_usage;
+            storage_deposit += env::storage_byte_cost() * Balance::from(required_storage_in_bytes);
+            refund_deposit(required_storage_in_bytes);
+        } else {
+            let released = std::cmp::min(
+                env::storage_byte_cost() * Balance::from(initial_storage_usage - final_storage_usage),
+                storage_deposit,
+            );
+            storage_deposit -= released;
+            let refund = released + env::attached_deposit();
+            if refund > 0 {
+                Promise::new(env::predecessor_account_id()).transfer(refund);
+            }
+        }
+        self.profile_storage_deposits.insert(account_id, &storage_deposit);
+    }
+
+    //drops the avatar of the account if it is the given token, the freed storage stays in its profile deposit
+    pub(crate) fn internal_unpin_avatar(&mut self, account_id: &AccountId, token_id: &TokenId) {
+        if self.avatars.get(account_id).as_ref() == Some(token_id) {
+            self.avatars.remove(account_id);
+        }
+    }
+
+    //checks the length and count of every field of a profile
+    pub(crate) fn internal_validate_profile(&self, profile: &Profile) {
+        if profile.display_name.chars().count() > MAX_DISPLAY_NAME_LEN {
+            env::panic_str(&format!("El nombre no puede superar {} caracteres", MAX_DISPLAY_NAME_LEN));
+        }
+        if profile.bio.chars().count() > MAX_BIO_LEN {
+            env::panic_str(&format!("La biografía no puede superar {} caracteres", MAX_BIO_LEN));
+        }
+
+        if profile.skills.len() > MAX_SKILLS {
+            env::panic_str(&format!("Un perfil puede tener hasta {} habilidades", MAX_SKILLS));
+        }
+        for skill in profile.skills.iter() {
+            if !self.skill_registry.contains(skill) {
+                env::panic_str(&format!("La habilidad {} no existe", skill));
+            }
+        }
+
+        if profile.languages.len() > MAX_LANGUAGES {
+            env::panic_str(&format!("Un perfil puede tener hasta {} idiomas", MAX_LANGUAGES));
+        }
+        if profile.languages.iter().any(|language| language.is_empty() || language.chars().count() > MAX_LANGUAGE_LEN) {
+            env::panic_str(&format!("Cada idioma debe tener entre 1 y {} caracteres", MAX_LANGUAGE_LEN));
+        }
+
+        if profile.links.len() > MAX_LINKS {
+            env::panic_str(&format!("Un perfil puede tener hasta {} enlaces", MAX_LINKS));
+        }
+        if profile.links.iter().any(|link| !link.starts_with("https://") || link.chars().count() > MAX_LINK_LEN) {
+            env::panic_str(&format!("Cada enlace debe empezar con https:// y tener hasta {} caracteres", MAX_LINK_LEN));
+        }
+
+        if profile.timezone.as_ref().is_some_and(|timezone| timezone.is_empty() || timezone.chars().count() > MAX_TIMEZONE_LEN) {
+            env::panic_str(&format!("La zona horaria debe tener entre 1 y {} caracteres", MAX_TIMEZONE_LEN));
+        }
+    }
+
+    pub(crate) fn internal_get_times_helped(&self, account_id: &AccountId) -> Option<TimesHelped> {
+        self.profile_times_helped.get(account_id).or_else(|| self.legacy_profile_times_helped.get(account_id).cloned())
+    }
+
+    pub(crate) fn internal_update_times_helped<F: FnOnce(&mut TimesHelped)>(
+        &mut self,
+        account_id: &AccountId,
+        update: F,
+    ) {
+        let mut times_helped = self.internal_get_times_helped(account_id).unwrap_or_default();
+        let previous_number = times_helped.number;
+        update(&mut times_helped);
+        self.legacy_profile_times_helped.remove(account_id);
+        self.profile_times_helped.insert(account_id, &times_helped);
+
+        //listed profiles move to their new place in the directory
+        if times_helped.number != previous_number {
+            if let Some(profile) = self.profiles.get(account_id).map(Profile::from) {
+                self.internal_remove_from_helped_index(account_id, &profile.skills, previous_number);
+                self.internal_add_to_helped_index(account_id, &profile.skills, times_helped.number);
+            }
+        }
+    }
+
+    //badges minted before the tier registry are only marked in the help counter
+    pub(crate) fn internal_badge_claimed(&self, account_id: &AccountId, tier_id: &String) -> bool {
+        if self.claimed_badges.get(account_id).is_some_and(|claimed| claimed.contains(tier_id)) {
+            return true;
+        }
+
+        let times_helped = self.internal_get_times_helped(account_id).unwrap_or_default();
+        match tier_id.as_str() {
+            "bronce" => times_helped.bronze,
+            "plata" => times_helped.silver,
+            "oro" => times_helped.gold,
+            _ => false,
+        }
+    }
+
+    pub(crate) fn internal_add_claimed_badge(&mut self, account_id: &AccountId, tier_id: &String) {
+        let mut claimed = self.claimed_badges.get(account_id).unwrap_or_else(|| {
+            UnorderedSet::new(
+                StorageKey::ClaimedBadgesInner {
+                    account_id_hash: hash_account_id(account_id),
+                }
+                .try_to_vec()
+                .unwrap(),
+            )
+        });
+        claimed.insert(tier_id);
+        self.claimed_badges.insert(account_id, &claimed);
+    }
+
+    //logs a profile event with the swapti standard
+    pub(crate) fn internal_log_profile_event(&self, event: EventLogVariant) {
+        let profile_log: EventLog = EventLog {
+            standard: SWAPTI_STANDARD_NAME.to_string(),
+            version: SWAPTI_EVENT_SPEC.to_string(),
+            event,
+        };
+        env::log_str(&profile_log.to_string());
+    }
+
+    //whether the access list keeps the account out of the marketplace
+    pub(crate) fn internal_is_banned(&self, account_id: &AccountId) -> bool {
+        let listed = self.access_list.contains(account_id);
+        match self.access_mode {
+            AccessMode::Blocklist => listed,
+            AccessMode::Allowlist => !listed,
+        }
+    }
+
+    //only accounts with a profile that aren't banned can create or attend requests
+    pub(crate) fn internal_can_use_marketplace(&self, account_id: &AccountId) -> bool {
+        !self.internal_is_banned(account_id) && self.internal_get_profile(account_id).is_some()
+    }
+
+    pub(crate) fn assert_can_use_marketplace(&self, account_id: &AccountId) {
+        if self.internal_is_banned(account_id) {
+            env::panic_str("La cuenta no tiene acceso al marketplace");
+        }
+        if self.internal_get_profile(account_id).is_none() {
+            env::panic_str("Debes crear un perfil para usar el marketplace");
+        }
+    }
+
+    //a banned account can't change its requests until allowed back, their helpers can still leave or time out
+    pub(crate) fn assert_request_not_frozen(&self, request: &Request) {
+        if self.internal_is_banned(&request.owner_id) {
+            env::panic_str("La solicitud está congelada");
+        }
+    }
+
+    //apply a change to the reputation counters of an account
+    pub(crate) fn internal_update_reputation<F: FnOnce(&mut Reputation)>(
+        &mut self,
+        account_id: &AccountId,
+        update: F,
+    ) {
+        let mut reputation = self.reputations.get(account_id).unwrap_or_default();
+        update(&mut reputation);
+        self.reputations.insert(account_id, &reputation);
+    }
+
+    //add a request to the set of requests in which the account was reviewed
+    pub(crate) fn internal_add_review_to_account(
+        &mut self,
+        account_id: &AccountId,
+        request_id: &u64,
+    ) {
+        let mut reviews_set = self.reviews_per_account.get(account_id).unwrap_or_else(|| {
+            UnorderedSet::new(
+                StorageKey::ReviewsPerAccountInner {
+                    account_id_hash: hash_account_id(account_id),
+                }
+                .try_to_vec()
+                .unwrap(),
+            )
+        });
+
+        reviews_set.insert(request_id);
+
+        self.reviews_per_account.insert(account_id, &reviews_set);
+    }
+
+    //true once the review window of a finished request is over (or it was never finished)
+    pub(crate) fn internal_review_window_closed(&self, request: &Request) -> bool {
+        match request.completed_at {
+            Some(completed_at) => env::block_timestamp() > completed_at.0 + REVIEW_WINDOW,
+            None => true,
+        }
+    }
+
+    //counts a revealed review towards the reputation of the reviewee and lists it among the reviews they received
+    pub(crate) fn internal_publish_review(
+        &mut self,
+        request_id: &u64,
+        reviewee_id: &AccountId,
+        review: &Review,
+        reviewee_is_helper: bool,
+    ) {
+        let rating = review.rating as u64;
+        self.internal_update_reputation(reviewee_id, |reputation| {
+            if reviewee_is_helper {
+                reputation.helper_rating_sum += rating;
+                reputation.helper_rating_count += 1;
+            } else {
+                reputation.requester_rating_sum += rating;
+                reputation.requester_rating_count += 1;
+            }
+        });
+        self.internal_add_review_to_account(reviewee_id, request_id);
+    }
+
     //remove a token from an owner (internal method and can't be called directly via CLI).
     pub(crate) fn internal_remove_token_from_owner(
         &mut self,
@@ -173,6 +1128,11 @@ impl Contract {
         //get the token object by passing in the token_id
         let token = self.tokens_by_id.get(token_id).expect("No token");
 
+        //covers nft_transfer, nft_transfer_call and nft_transfer_payout
+        if self.soulbound_tokens.contains(token_id) {
+            env::panic_str("Este token es intransferible");
+        }
+
         //if the sender doesn't equal the owner, we check if the sender is in the approval list
 		if sender_id != &token.owner_id {
 			//if the token's approved account IDs doesn't contain the sender, we panic
@@ -206,6 +1166,8 @@ impl Contract {
 
         //we remove the token from it's current owner's set
         self.internal_remove_token_from_owner(&token.owner_id, token_id);
+        //a profile never shows a token its owner no longer holds
+        self.internal_unpin_avatar(&token.owner_id, token_id);
         //we then add the token to the receiver_id's set
         self.internal_add_token_to_owner(receiver_id, token_id);
 
@@ -1,20 +1,31 @@
 use std::collections::HashMap;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LazyOption, LookupMap, UnorderedMap, UnorderedSet};
-use near_sdk::json_types::{Base64VecU8, U128};
+use near_sdk::collections::{LazyOption, LookupMap, LookupSet, TreeMap, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
     env, log, near_bindgen, AccountId, Balance, CryptoHash, PanicOnDefault, Promise, PromiseOrValue, Gas
 };
-type U128String = U128;
 
 use crate::internal::*;
 pub use crate::metadata::*;
 pub use crate::mint::*;
 pub use crate::nft_core::*;
+pub use crate::approval::*;
 pub use crate::royalty::*;
 pub use crate::events::*;
 pub use crate::profile::*;
+pub use crate::review::*;
+pub use crate::reputation::*;
+pub use crate::application::*;
+pub use crate::bounty::*;
+pub use crate::dispute::*;
+pub use crate::tag::*;
+pub use crate::revision::*;
+pub use crate::message::*;
+pub use crate::participant::*;
+pub use crate::access::*;
+pub use crate::contact::*;
 pub use crate::xcc::*;
 
 mod internal;
@@ -22,21 +33,42 @@ mod enumeration;
 mod metadata; 
 mod mint; 
 mod nft_core; 
+mod approval;
 mod royalty; 
 mod events;
 mod profile;
+mod review;
+mod reputation;
+mod application;
+mod bounty;
+mod dispute;
+mod tag;
+mod revision;
+mod message;
+mod participant;
+mod access;
+mod contact;
+mod migrate;
 mod xcc;
+#[cfg(test)]
+mod test_utils;
 
 
 /// This spec can be treated like a version of the standard.
 pub const NFT_METADATA_SPEC: &str = "1.0.0";
 /// This is the name of the NFT standard we're using
 pub const NFT_STANDARD_NAME: &str = "nep171";
+/// Version of the events emitted by the request marketplace
+pub const SWAPTI_EVENT_SPEC: &str = "1.0.0";
+/// Standard name used for the request marketplace events
+pub const SWAPTI_STANDARD_NAME: &str = "swapti";
 pub const ICON: &str = "data:image/jpeg;base64,/9j/4AAQSkZJRgABAQAAAQABAAD/4gHYSUNDX1BST0ZJTEUAAQEAAAHIAAAAAAQwAABtbnRyUkdCIFhZWiAAAAAAAAAAAAAAAABhY3NwAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAQAA9tYAAQAAAADTLQAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAlkZXNjAAAA8AAAACRyWFlaAAABFAAAABRnWFlaAAABKAAAABRiWFlaAAABPAAAABR3dHB0AAABUAAAABRyVFJDAAABZAAAAChnVFJDAAABZAAAAChiVFJDAAABZAAAAChjcHJ0AAABjAAAADxtbHVjAAAAAAAAAAEAAAAMZW5VUwAAAAgAAAAcAHMAUgBHAEJYWVogAAAAAAAAb6IAADj1AAADkFhZWiAAAAAAAABimQAAt4UAABjaWFlaIAAAAAAAACSgAAAPhAAAts9YWVogAAAAAAAA9tYAAQAAAADTLXBhcmEAAAAAAAQAAAACZmYAAPKnAAANWQAAE9AAAApbAAAAAAAAAABtbHVjAAAAAAAAAAEAAAAMZW5VUwAAACAAAAAcAEcAbwBvAGcAbABlACAASQBuAGMALgAgADIAMAAxADb/2wBDAAMCAgICAgMCAgIDAwMDBAYEBAQEBAgGBgUGCQgKCgkICQkKDA8MCgsOCwkJDRENDg8QEBEQCgwSExIQEw8QEBD/2wBDAQMDAwQDBAgEBAgQCwkLEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBD/wAARCABgAGADASIAAhEBAxEB/8QAHQAAAQQDAQEAAAAAAAAAAAAACAADBgcBBQkCBP/EADwQAAECBQIEBAIGCAcAAAAAAAECAwAEBQYRBxIIITFhQVFxgROhFCJScoKxFSMyM0JiY5EkU5KTwtLw/8QAHAEAAgMBAAMAAAAAAAAAAAAABQYCBAcDAAEI/8QAMxEAAQMDAAcGBQQDAAAAAAAAAQACAwQFEQYSEyExUXEiQWGBkdEHFKGxwRUjYvBCgvH/2gAMAwEAAhEDEQA/ANnyhRjB84WD5w96i+lNuswoxg+cSGxbFr2oNfaoFBZ3OK+s68r92w34rUfL8zyj04Bg1ncFxnro6aN00zgGtGST3BaOXl5ibfRLSrDjzzhCUNtpKlKPkAOZiyrb4c9UriaTMGitUxlXMLn3fhk/gGVf3EFBpto/aWm0kgU6UTNVJSf11QeSC6o45hP2E9h75idQHmuJziIeqyS8fFGXXMdrjGqP8nZ39Bux556BCYjhEvpScruGhpPludP/AAj457hO1JlkKXKTtGmyBySiYUkn03JA+cF/Cjh+oTJfb8Sb805Lmn/UfjCAK5dK9QrRC3K7as6yyjq+hIdaHfejIiJ+0dKFJCgUqAIIwQfGKv1E4e7IvhpyakpZNFqhBKZmVQAhav6jfRXqMHvFqG5AnEox0TVafikyRwjucer/ACbkjzad/oT0QT+0ZiWagaX3ZpvPfRbgkj9HcVhicaypl30V4H+U4MRLl5/OCrdV41mnIWnU1wiq4hNA8OaeBHBYz2ELPYR5yIWRFvUVDbL2kKWoIQnKlHAAHMmDm0Q04Y08syXZfl0pq1QSmZn1+IWRybz5JBx65PjAn6JW4zdOp1Cpswncw2/9KdTjkUtArwexKQPeDz6QDu8pbiEdSsu+It3fiO3MO49p3j3AfQn0Sht99iVYcmZl1DTTSSta1qwlKQMkknoIcil+K2vz9I06ZkJJSkIqs8iWfWP8sJUsp9ykewMCYIjPIIx3rOLZQm5VkdIDjWOM8uf0T9Z4ptMaVPOSUuqo1ENq2l6VYHwyexUoEjviNlb3EdpVX3EsKri6a6rkEzzJbGfvjKR7mAiz3hZEMBs8JGAStafoBaXR6rXPB55H2xhdJZOdk6hLInJCaZmWHRlDrSwtCh2I5GH459WTqVd+n06mbtyrutt7suSrh3MO/eQeXuMHvBL25xD0i+bMrEu0BS7nYpr62pVSspecDZILKj1ORnb1HfrAyotcsBy3eP7xSTd9C6y3ODoTrxk8RxGeY5eI3dFG9buIelqcnrHt+iyNXYSVMTkxOJK2SociltIxkg/xZ6jl5wM5USSenaMKWVKKlKJJOST4mMbhB+npGU7dVi1yz2ymslOIKYdTzPP/AIvO4RjcI8bu0Ld2gls1Lbq3+FyZYZ1alUOkBT0nMNt5+1tB/JJg045wW1cVQtWvyFxUte2ZkH0vI58lYPNJ7EZB7GD50+1AoOo1vM12iTCSSAmYlyofEl3PFCh+R8RzhZvlM9rxMBuxhZbp3RyuqGVoGWkBp8CCfvlSaI/fNk0bUC3Ji264hXwHsKQ4jAW04P2Vpz4j8iREghQDa4sIc3iEiQyvgkEsRw4HIPIoINQuHy/bFccmZeRVWKYCSmak0FSkp/nb6p5eo7xWBJSSlSSCORBjpdEBvrRHT6/W3HKjR0Sk8vJE7JgNO581Y5L/ABAwepr1jszjzHstFtmnz2gR3Bmf5N/I9vRAdu7RlDq21BaFFKhzBBwRFlap6C3bptvqKR+lKNu5TjKDloeHxU/w+vTv4RWO4wwQvjqG68ZyE/UtygrYhLA4OaV73doyDmG9xj0F+ETcw43K5HM0nemt3pC394a3GNjQKBWLnqSaRQpJU3OLbccQyjG5QQkqVjzOAeXjF0tDRrHggjqgMBc44AXxb+8b2z75uWxKsis2zU3JV4YC09W3U/ZWnoof+GI0DiHWXFMvNqQtBKVJUMFJHUEeBjzuMeOhbI3VcMgqMjmTMLHjIPceCMfTniptK5Es068Uih1FX1S8STKuK7K6o/Fy7xdspOSk/LNzkjMtTDDo3IdaWFJUPMEcjHMvcYk9mamXrYM0mYtmuPy7YVuVLKO9hz7yDy9+veF+r0dY/Lqc4PI8P76pMuOiUMpL6N2qeR3j14j6ronCindFeIWm6mPJt6syaKdXktlaUoOWZkDqUZ5ggc9p8OhMXFCtUU0tLIY5RgpFq6SailMM4wQm32GJphyWmWUOsupKFtrSFJUk8iCD1EBPxE6VsacXQ1PUZrZRaxvcl0Zz8BwH67fpzBHY48INyKG4xJdlendNmV4DrNWQEHxIU05kfIH2ghZZ3xVbWDg7cUZ0YrpKSvaxp7L9xH29EIe7vCCu8NBcLcYezGtYEya3CCK4Q7BnJy4JnUGbZUiSkGlysopSf3ry+SiOyU5HqrtFSaS0rT+uXbL0/UWsTNPkHCPhrbwG1uZ5IcX1Qk+YHuOsdAqLS6TRaVK0uhSrMtIS7YTLtsj6gR4Y889c+OcwH0hr/lo/lmg5d392PDmkPSG7OhiNKwHLhx7sd+FVusPDtb2o/wAWtUdTdKr5GS8lP6qZP9VI8f5hz88wIl6ae3fYE+qRuiivyuFFKH9u5l3uhY5H846Nx88/T5CqSq5GpSTE3LujC2nmwtCh3B5QBt98mogI3jWb9R0KA23SGooWiN/aZ48R0P4XMjcIW6DquPhl0kuFxT6KE5S3lcyqQeLY/wBByn5RGGuDjTxEwHHa7XHGgc/D+I2M+4RDEzSOhc3Lsg9EyM0po3DLsg9PZVDwq2zUK1qhL1phpQlKKy48+5jluWgoQjPmSon0SYNqNHaFl21YtIRRLXpbcnLJO5W3mtxX2lqPNR7mN5Cnda4XCo2rRgAYHRJ93uP6lUbUDAAwOiUDRxoXAy3TbdthLqS64+5PuIB5pSlOxBPqVr/sYJGcm5aQlXp6dfQzLy6FOuuLVhKEgZJJ8ABHPjWbUA6kX9UbhayJNJEtJJPgwjISfc5V+KL+jlG6oq9rjss3+fAe6t6O05kqxN3M3+fAe6hhVg4Jhbu4hoEiM7u0aCY1oAmKb3d4tbSniKvHTUNUx9f6XoiSB9DfWdzSf6S/4fu8x6dYqTd3hbu8TqKOKrZs5m5CDT7OpZqSjIXQqxNedNr/AGUJp9dakp1WAZKeUGXQfIZOFfhJiwgQQCDkHoY5ZhZByFc4llu6takWqEIoV51OXab/AGWlPFxsdti8j5QqVWh4JzTPx4H3Hsl2exNJzC7HgfddIYUBDTOMHViSbDc6mj1Ajqt6UKFH/bUkfKNqONTUDbg2zQM+e17/ALwJdorcWnAAPmqJs1UDux6oyY+CtV6jW5T3KrXqnLSEo0MrefcCEjtz6nsIC+scX2rFRZUzImk0zdy3y8ruWPQuFQ+UVRcd43Rd0z9MuavztSdH7JmHioJ+6Og9hFqm0RqXuzUODR4bz7LtDY5HH91wA8N5Vy6+8Rzt+octKzlPS1BCv8Q+obXJ0jwx1S3446nxx0ih93rDWe8Ld3h1pKCKhiEMIwPv4lM9MyOkjEcQwE7uELd2hrcPOMbhFvZrttiv/9k=";
-const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(10_000_000_000_000);
-const GAS_FOR_NFT_TRANSFER_CALL: Gas = Gas(25_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER.0);
 const MIN_GAS_FOR_NFT_TRANSFER_CALL: Gas = Gas(100_000_000_000_000);
 const NO_DEPOSIT: Balance = 0;
+//time the helper has to respond before the owner can release them (3 days in nanoseconds)
+pub const RELEASE_HELPER_AFTER: u64 = 3 * 24 * 60 * 60 * 1_000_000_000;
+//time the helper has to finish an in progress request when the owner does not set one (14 days in nanoseconds)
+pub const DEFAULT_WORK_TIMEOUT: u64 = 14 * 24 * 60 * 60 * 1_000_000_000;
 
 /// Helper structure for keys of the persistent collections.
 #[derive(BorshSerialize)]
@@ -50,13 +82,58 @@ pub enum StorageKey {
     TokensPerTypeInner { token_type_hash: CryptoHash },
     TokenTypesLocked,
     TokensToMintCounter,
+    HelpedRequestsPerHelper,
+    HelpedRequestsPerHelperInner { account_id_hash: CryptoHash },
+    ReviewsPerAccount,
+    ReviewsPerAccountInner { account_id_hash: CryptoHash },
+    Reputations,
+    ApplicationsPerRequest,
+    ApplicationsPerRequestInner { request_id: u64 },
+    FtTokenWhitelist,
+    Arbiters,
+    RequestsPerHelper,
+    RequestsPerHelperInner { account_id_hash: CryptoHash },
+    RequestsPerStatus,
+    RequestsPerStatusInner { status: RequestStatus },
+    TagRegistry,
+    RequestsPerTag,
+    RequestsPerTagInner { tag_hash: CryptoHash },
+    RevisionsPerRequest,
+    RevisionsPerRequestInner { request_id: u64 },
+    MessagesPerRequest,
+    MessagesPerRequestInner { request_id: u64 },
+    Profiles,
+    ProfileTimesHelped,
+    ProfileStorageDeposits,
+    ContactsPerRequest,
+    SkillRegistry,
+    AccountsPerSkill,
+    AccountsPerSkillInner { skill_hash: CryptoHash },
+    AccessList,
+    Avatars,
+    BadgeTiers,
+    ClaimedBadges,
+    ClaimedBadgesInner { account_id_hash: CryptoHash },
+    SoulboundTokens,
+    Onboarded,
+    RequestsPerOwner,
+    RequestsPerOwnerInner { account_id_hash: CryptoHash },
+    ProfilesByHelped,
+    ProfilesByHelpedPerSkill,
+    ProfilesByHelpedPerSkillInner { skill_hash: CryptoHash },
+    ContactsPerRequestInner { request_id: u64 },
+    RequestStorageBalances,
+    Requests,
 }
-#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, PartialEq, Debug)]
 #[serde(crate = "near_sdk::serde")]
 pub enum RequestStatus {
     Open,
     InProgress,
     Complete,
+    Cancelled,
+    Disputed,
+    Expired,
 }
 
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
@@ -66,17 +143,52 @@ pub struct Review {
     comment: String,
 }
 
+//profile layout of the first version, the email is no longer returned
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
-pub struct Profile {
+pub struct ProfileV1 {
+    #[serde(skip_serializing, default)]
     email: String,
     bio: String
 }
 
-#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(default)]
+pub struct Profile {
+    display_name: String,
+    bio: String,
+    //skills from the registry
+    skills: Vec<String>,
+    languages: Vec<String>,
+    links: Vec<String>,
+    timezone: Option<String>,
+}
+
+//profiles are stored with their version, new layouts are added as variants and read with into()
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum VersionedProfile {
+    V1(ProfileV1),
+    V2(Profile),
+}
+
+impl From<VersionedProfile> for Profile {
+    fn from(profile: VersionedProfile) -> Self {
+        match profile {
+            VersionedProfile::V1(profile) => Profile {
+                bio: profile.bio,
+                ..Default::default()
+            },
+            VersionedProfile::V2(profile) => profile,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone, Default)]
 #[serde(crate = "near_sdk::serde")]
 pub struct TimesHelped {
     number: u64,
+    //set by the minting methods replaced by mint_badge, read as claims of the default tiers
     bronze: bool,
     silver: bool,
     gold: bool
@@ -88,10 +200,36 @@ pub struct Request {
     request_id: u64,
     owner_id: AccountId,
     description: String,
+    //first helper still taking part in the request
     helper: Option<AccountId>,
     status: RequestStatus,
-    review_owner: Option<Review>,
-    review_helper: Option<Review>
+    //when the first helper took the request
+    attended_at: Option<U64>,
+    //when the request was finished, starts the review window
+    completed_at: Option<U64>,
+    //NEAR locked by the owner, paid to the helper when the request is finished
+    bounty: U128,
+    escrow: EscrowStatus,
+    //fungible token bounty, funded through ft_transfer_call on a whitelisted token
+    ft_token_id: Option<AccountId>,
+    ft_bounty: U128,
+    ft_escrow: EscrowStatus,
+    //latest dispute opened on the request
+    dispute: Option<Dispute>,
+    //after this moment the request can be expired if nobody finished it
+    deadline: Option<U64>,
+    //time the helper has to finish the request once it is accepted
+    work_timeout: U64,
+    //when the request was created
+    created_at: U64,
+    //tags from the registry describing the area of the request
+    tags: Vec<String>,
+    //storage deposit paid by the owner, refunded when the request is deleted
+    storage_deposit: U128,
+    //number of helpers the request takes, more than one for group sessions
+    capacity: u64,
+    //every helper taking part in the request
+    participants: Vec<Participant>,
 }
 
 
@@ -115,9 +253,95 @@ pub struct Contract {
 
     pub requests_per_owner: LookupMap<AccountId, UnorderedSet<u64>>,
     pub requests: UnorderedMap<u64, Request>,
-    pub profiles: HashMap<AccountId, Profile>,
-    pub profile_times_helped: HashMap<AccountId, TimesHelped>
+    //requests of the previous version, emptied by migrate_requests
+    pub legacy_requests: UnorderedMap<u64, migrate::OldRequest>,
+    pub profiles: UnorderedMap<AccountId, VersionedProfile>,
+    pub profile_times_helped: LookupMap<AccountId, TimesHelped>,
+
+    //keeps track of the completed requests that were credited to each helper
+    pub helped_requests_per_helper: LookupMap<AccountId, UnorderedSet<u64>>,
+
+    //keeps track of the requests in which an account received a review
+    pub reviews_per_account: LookupMap<AccountId, UnorderedSet<u64>>,
+
+    //keeps track of the reputation counters of every account
+    pub reputations: LookupMap<AccountId, Reputation>,
+
+    //keeps track of the pending applications of every open request
+    pub applications_per_request: LookupMap<u64, UnorderedMap<AccountId, Application>>,
+
+    //fungible token contracts accepted for request bounties
+    pub ft_token_whitelist: UnorderedSet<AccountId>,
+
+    //accounts allowed to resolve disputes, managed by the contract owner
+    pub arbiters: UnorderedSet<AccountId>,
+
+    //keeps track of the requests attended by each helper, from acceptance until the helper is removed
+    pub requests_per_helper: LookupMap<AccountId, UnorderedSet<u64>>,
+
+    //keeps track of the requests in each status, keyed by request id (ids follow creation order) with the creation time
+    pub requests_per_status: LookupMap<RequestStatus, TreeMap<u64, u64>>,
+
+    //tags requests can use, managed by the contract owner
+    pub tag_registry: UnorderedSet<String>,
+
+    //keeps track of the requests that use each tag
+    pub requests_per_tag: LookupMap<String, UnorderedSet<u64>>,
+
+    //id of the next request, ids are never reused even if requests are deleted
+    pub next_request_id: u64,
+
+    //keeps track of the previous descriptions of every edited request
+    pub revisions_per_request: LookupMap<u64, Vector<Revision>>,
+
+    //keeps track of the message thread of every request
+    pub messages_per_request: LookupMap<u64, Vector<Message>>,
+
+    //profiles and help counters of the previous version, emptied by migrate_profiles
+    pub legacy_profiles: HashMap<AccountId, ProfileV1>,
+    pub legacy_profile_times_helped: HashMap<AccountId, TimesHelped>,
+
+    //storage deposit paid by each account for its profile
+    pub profile_storage_deposits: LookupMap<AccountId, Balance>,
+
+    //contact details shared in each request, keyed by author and recipient
+    pub contacts_per_request: LookupMap<u64, UnorderedMap<(AccountId, AccountId), ContactShare>>,
+
+    //NEAR each account deposited to pay the storage of the requests it creates through ft_transfer_call
+    pub request_storage_balances: LookupMap<AccountId, Balance>,
+
+    //skills profiles can list, managed by the contract owner
+    pub skill_registry: UnorderedSet<String>,
+
+    //keeps track of the accounts that list each skill in their profile
+    pub accounts_per_skill: LookupMap<String, UnorderedSet<AccountId>>,
+
+    //whether the access list bans or allows the accounts in it
+    pub access_mode: AccessMode,
+
+    //accounts banned from or allowed into the marketplace, depending on the access mode
+    pub access_list: UnorderedSet<AccountId>,
+
+    //token each account pinned as the picture of its profile
+    pub avatars: LookupMap<AccountId, TokenId>,
+
+    //badges helpers can mint, by tier ID
+    pub badge_tiers: UnorderedMap<String, BadgeTier>,
+
+    //tiers each account already minted
+    pub claimed_badges: LookupMap<AccountId, UnorderedSet<String>>,
+
+    //tokens that can't leave the account they were minted to
+    pub soulbound_tokens: UnorderedSet<TokenId>,
+
+    //accounts that already got the onboarding SWAPTI, kept when the profile is deleted
+    pub onboarded: LookupSet<AccountId>,
+
+    //profiles by the number of people each account helped, so the directory can be read in that order
+    pub profiles_by_helped: TreeMap<(u64, AccountId), ()>,
 
+    //the same order for the accounts that list each skill
+    pub profiles_by_helped_per_skill: LookupMap<String, TreeMap<(u64, AccountId), ()>>,
 }
 
 #[near_bindgen]
@@ -142,7 +366,7 @@ impl Contract {
     #[init]
     pub fn new(owner_id: AccountId, metadata: NFTContractMetadata) -> Self {
         //create a variable of type Self with all the fields initialized. 
-        let this = Self {
+        let mut this = Self {
             tokens_per_owner: LookupMap::new(StorageKey::TokensPerOwner.try_to_vec().unwrap()),
             tokens_by_id: LookupMap::new(StorageKey::TokensById.try_to_vec().unwrap()),
             token_metadata_by_id: UnorderedMap::new(
@@ -153,32 +377,114 @@ impl Contract {
                 StorageKey::NFTContractMetadata.try_to_vec().unwrap(),
                 Some(&metadata),
             ),
-            requests_per_owner: LookupMap::new(StorageKey::TokensPerOwner.try_to_vec().unwrap()),
-            requests: UnorderedMap::new(b"m"),
-            profiles: HashMap::new(),
-            profile_times_helped: HashMap::new()
+            requests_per_owner: LookupMap::new(StorageKey::RequestsPerOwner.try_to_vec().unwrap()),
+            requests: UnorderedMap::new(StorageKey::Requests.try_to_vec().unwrap()),
+            legacy_requests: UnorderedMap::new(b"m"),
+            profiles: UnorderedMap::new(StorageKey::Profiles.try_to_vec().unwrap()),
+            profile_times_helped: LookupMap::new(StorageKey::ProfileTimesHelped.try_to_vec().unwrap()),
+            helped_requests_per_helper: LookupMap::new(
+                StorageKey::HelpedRequestsPerHelper.try_to_vec().unwrap(),
+            ),
+            reviews_per_account: LookupMap::new(StorageKey::ReviewsPerAccount.try_to_vec().unwrap()),
+            reputations: LookupMap::new(StorageKey::Reputations.try_to_vec().unwrap()),
+            applications_per_request: LookupMap::new(
+                StorageKey::ApplicationsPerRequest.try_to_vec().unwrap(),
+            ),
+            ft_token_whitelist: UnorderedSet::new(StorageKey::FtTokenWhitelist.try_to_vec().unwrap()),
+            arbiters: UnorderedSet::new(StorageKey::Arbiters.try_to_vec().unwrap()),
+            requests_per_helper: LookupMap::new(StorageKey::RequestsPerHelper.try_to_vec().unwrap()),
+            requests_per_status: LookupMap::new(StorageKey::RequestsPerStatus.try_to_vec().unwrap()),
+            tag_registry: UnorderedSet::new(StorageKey::TagRegistry.try_to_vec().unwrap()),
+            requests_per_tag: LookupMap::new(StorageKey::RequestsPerTag.try_to_vec().unwrap()),
+            next_request_id: 0,
+            revisions_per_request: LookupMap::new(StorageKey::RevisionsPerRequest.try_to_vec().unwrap()),
+            messages_per_request: LookupMap::new(StorageKey::MessagesPerRequest.try_to_vec().unwrap()),
+            legacy_profiles: HashMap::new(),
+            legacy_profile_times_helped: HashMap::new(),
+            profile_storage_deposits: LookupMap::new(StorageKey::ProfileStorageDeposits.try_to_vec().unwrap()),
+            contacts_per_request: LookupMap::new(StorageKey::ContactsPerRequest.try_to_vec().unwrap()),
+            request_storage_balances: LookupMap::new(StorageKey::RequestStorageBalances.try_to_vec().unwrap()),
+            skill_registry: UnorderedSet::new(StorageKey::SkillRegistry.try_to_vec().unwrap()),
+            accounts_per_skill: LookupMap::new(StorageKey::AccountsPerSkill.try_to_vec().unwrap()),
+            access_mode: AccessMode::Blocklist,
+            access_list: UnorderedSet::new(StorageKey::AccessList.try_to_vec().unwrap()),
+            avatars: LookupMap::new(StorageKey::Avatars.try_to_vec().unwrap()),
+            badge_tiers: UnorderedMap::new(StorageKey::BadgeTiers.try_to_vec().unwrap()),
+            claimed_badges: LookupMap::new(StorageKey::ClaimedBadges.try_to_vec().unwrap()),
+            soulbound_tokens: UnorderedSet::new(StorageKey::SoulboundTokens.try_to_vec().unwrap()),
+            onboarded: LookupSet::new(StorageKey::Onboarded.try_to_vec().unwrap()),
+            profiles_by_helped: TreeMap::new(StorageKey::ProfilesByHelped.try_to_vec().unwrap()),
+            profiles_by_helped_per_skill: LookupMap::new(StorageKey::ProfilesByHelpedPerSkill.try_to_vec().unwrap()),
         };
 
+        for tier in default_badge_tiers() {
+            this.badge_tiers.insert(&tier.tier_id, &tier);
+        }
+
         //return the Contract object
         this
     }
 
-    pub fn create_request(&mut self, description: String) -> String {
-        let request_id = self.requests.len() as u64;
-
-        let request = Request {
-            request_id: request_id.clone(),
-            owner_id: env::signer_account_id().clone(),
-            description,
-            helper: None,
-            status: RequestStatus::Open,
-            review_owner: None,
-            review_helper: None
-        };
+    /*
+        The owner attaches the storage of the request plus an optional NEAR bounty, which is locked
+        until the request finishes. The owner can also set when the request expires and tag it.
+    */
+    #[payable]
+    pub fn create_request(
+        &mut self,
+        description: String,
+        bounty: Option<U128>,
+        deadline: Option<U64>,
+        work_timeout: Option<U64>,
+        tags: Option<Vec<String>>,
+        capacity: Option<u64>,
+    ) -> String {
+        self.assert_can_use_marketplace(&env::signer_account_id());
+
+        let bounty = bounty.unwrap_or(U128(0));
+        let attached_deposit = env::attached_deposit();
+        let initial_storage_usage = env::storage_usage();
+
+        let mut request = self.internal_new_request(&env::signer_account_id(), description);
+        self.internal_set_deadlines(&mut request, deadline, work_timeout);
+        self.internal_set_tags(&mut request, tags.unwrap_or_default());
+
+        let capacity = capacity.unwrap_or(1);
+        if capacity == 0 || capacity > MAX_CAPACITY {
+            env::panic_str(&format!("La solicitud debe aceptar entre 1 y {} ayudantes", MAX_CAPACITY));
+        }
+        //bounties are paid to a single helper
+        if capacity > 1 && bounty.0 > 0 {
+            env::panic_str("Las solicitudes grupales no pueden tener recompensa");
+        }
+        request.capacity = capacity;
 
-        self.requests.insert(&request_id, &request);
-        self.internal_add_request_to_owner(&env::signer_account_id(), &request_id);
-        return "Solicitud creada con éxito".to_string();
+        if bounty.0 > 0 {
+            request.bounty = bounty;
+            request.escrow = EscrowStatus::Locked;
+        }
+        self.internal_add_request(&request);
+
+        //the deposit has a fixed size, so recording it doesn't change the storage used
+        let required_storage_in_bytes = env::storage_usage() - initial_storage_usage;
+        let storage_cost = env::storage_byte_cost() * Balance::from(required_storage_in_bytes);
+        request.storage_deposit = U128(storage_cost);
+        self.requests.insert(&request.request_id, &request);
+
+        if attached_deposit < bounty.0 + storage_cost {
+            env::panic_str(&format!(
+                "Debes adjuntar {} yoctoNEAR para cubrir la recompensa y el almacenamiento",
+                bounty.0 + storage_cost
+            ));
+        }
+
+        //anything attached on top of the bounty and the storage goes back to the caller
+        let refund = attached_deposit - bounty.0 - storage_cost;
+        if refund > 1 {
+            Promise::new(env::predecessor_account_id()).transfer(refund);
+        }
+
+        "Solicitud creada con éxito".to_string()
     }
 
     pub fn request_for_owner(
@@ -199,7 +505,53 @@ impl Contract {
         request.iter()
             .skip(start as usize) 
             .take(limit.unwrap_or(50) as usize) 
-            .map(|request_id| self.get_request(request_id.clone()).unwrap())
+            .map(|request_id| self.get_request(request_id).unwrap())
+            .collect()
+    }
+
+    //requests the account is attending or attended, optionally filtered by status
+    pub fn requests_for_helper(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+        status: Option<RequestStatus>,
+    ) -> Vec<Request> {
+        let request_set = if let Some(request_set) = self.requests_per_helper.get(&account_id) {
+            request_set
+        } else {
+            return vec![];
+        };
+
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        request_set.iter()
+            .map(|request_id| self.get_request(request_id).unwrap())
+            .filter(|request| status.is_none() || status.as_ref() == Some(&request.status))
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    //requests currently in the given status, newest first
+    pub fn requests_by_status(
+        &self,
+        status: RequestStatus,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Request> {
+        let status_index = if let Some(status_index) = self.requests_per_status.get(&status) {
+            status_index
+        } else {
+            return vec![];
+        };
+
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        status_index.iter_rev()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .map(|(request_id, _)| self.get_request(request_id).unwrap())
             .collect()
     }
 
@@ -213,126 +565,284 @@ impl Contract {
         self.requests.keys()
             .skip(start as usize) 
             .take(limit.unwrap_or(50) as usize) 
-            .map(|request_id| self.get_request(request_id.clone()).unwrap())
+            .map(|request_id| self.get_request(request_id).unwrap())
             .collect()
     }
 
     pub fn get_request(&self, request_id: u64) -> Option<Request> {
-        //if there is some token ID in the tokens_by_id collection
-        if let Some(request) = self.requests.get(&request_id) {
-            //we'll get the metadata for that token
-            let request_data = self.requests.get(&request_id).unwrap();
-            //we return the JsonToken (wrapped by Some since we return an option)
-            Some(Request {
-                request_id: request_id,
-                owner_id: request_data.owner_id,
-                description: request_data.description,
-                helper: request_data.helper,
-                status: request_data.status,
-                review_owner: request_data.review_owner,
-                review_helper: request_data.review_helper
-            })
-        } else {
-            None
-        }
+        self.requests.get(&request_id)
     }
 
 
     pub fn get_number_swaps(&self, accountid: AccountId) -> TimesHelped{
-        let p = self.profile_times_helped.get(&accountid.clone());
-        if p.is_none() {
-            let profile_info = TimesHelped {
-                number: 0,
-                bronze: false,
-                silver: false,
-                gold: false,
-            };
-            return profile_info;
-        }
+        self.internal_get_times_helped(&accountid).unwrap_or_default()
+    }
 
-        let info = p.unwrap();
-        let profile_info = TimesHelped {
-            number: info.number,
-            bronze: info.bronze,
-            silver: info.silver,
-            gold: info.gold,
+    //completed requests that were counted towards the helper's badges
+    pub fn helped_requests(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Request> {
+        let helped_set = if let Some(helped_set) = self.helped_requests_per_helper.get(&account_id) {
+            helped_set
+        } else {
+            return vec![];
         };
-        return profile_info;
+
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        helped_set.iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .map(|request_id| self.get_request(request_id).unwrap())
+            .collect()
     }
 
-    pub fn attend_request(&mut self, request_id: u64) -> Option<Request> {
-        if let Some(request) = self.requests.get(&request_id) {
-            let request_data = self.requests.get(&request_id).unwrap();
+    //helpers apply to an open request with a short pitch, the owner then picks one with accept_application
+    #[payable]
+    pub fn attend_request(&mut self, request_id: u64, pitch: Option<String>) -> Application {
+        if let Some(request_data) = self.requests.get(&request_id) {
+            let applicant_id = env::signer_account_id();
+            self.assert_can_use_marketplace(&applicant_id);
+            self.assert_request_not_frozen(&request_data);
 
-            if request_data.owner_id == env::signer_account_id().clone() {
+            if request_data.owner_id == applicant_id {
                 env::panic_str("No puedes atender tu propia solicitud");
             }
 
-            if request_data.status == RequestStatus::InProgress || request_data.status == RequestStatus::Complete {
-                env::panic_str("La solicitud ya fué atendida");
+            if !self.internal_accepts_helpers(&request_data) {
+                env::panic_str("La solicitud ya fué atendida o fué cancelada");
             }
 
-            let new_request = Request {
-                request_id: request_id,
-                owner_id: request_data.owner_id,
-                description: request_data.description,
-                helper: Some(env::signer_account_id().clone()),
-                status: RequestStatus::InProgress,
-                review_owner: request_data.review_owner,
-                review_helper: request_data.review_helper
-            };
-
-            self.requests.insert(&request_id, &new_request);
+            if request_data.participants.iter().any(|participant| participant.account_id == applicant_id) {
+                env::panic_str("Ya participas en esta solicitud");
+            }
 
-            return Some(new_request);
+            if self.internal_deadline_passed(&request_data) {
+                env::panic_str("La solicitud ya venció");
+            }
 
+            let pitch = pitch.unwrap_or_default();
+            if pitch.chars().count() > MAX_PITCH_LEN {
+                env::panic_str(&format!("La presentación no puede superar {} caracteres", MAX_PITCH_LEN));
+            }
 
+            self.internal_add_application(&request_id, &applicant_id, pitch)
         } else {
             env::panic_str("No existe la solicitud a atender");
         }
     }
 
     pub fn finish_request(&mut self, request_id: u64) -> Option<Request> {
-        if let Some(request) = self.requests.get(&request_id) {
-            let request_data = self.requests.get(&request_id).unwrap();
-
-            if request_data.owner_id != env::signer_account_id().clone() {
+        if let Some(request_data) = self.requests.get(&request_id) {
+            if request_data.owner_id != env::signer_account_id() {
                 env::panic_str("Solo el creador de la solicitud puede finalizarla");
             }
 
-            if request_data.status == RequestStatus::Open || request_data.status == RequestStatus::Complete {
+            if request_data.status != RequestStatus::InProgress {
                 env::panic_str("La solicitud aún no es atendida o ya finalizó");
             }
 
-            let new_request = Request {
-                request_id: request_id,
-                owner_id: request_data.owner_id,
-                description: request_data.description,
-                helper: request_data.helper.clone(),
-                status: RequestStatus::Complete,
-                review_owner: request_data.review_owner,
-                review_helper: request_data.review_helper
+            self.assert_request_not_frozen(&request_data);
+
+            let new_request = self.internal_complete_request(request_data);
+
+            Some(new_request)
+        } else {
+            env::panic_str("No existe la solicitud a atender");
+        }
+    }
+
+    //the owner closes a request nobody is working on
+    pub fn cancel_request(&mut self, request_id: u64) -> Option<Request> {
+        if let Some(request_data) = self.requests.get(&request_id) {
+            if request_data.owner_id != env::signer_account_id() {
+                env::panic_str("Solo el creador de la solicitud puede cancelarla");
+            }
+
+            if request_data.status != RequestStatus::Open {
+                env::panic_str("Solo se pueden cancelar solicitudes abiertas");
+            }
+
+            self.assert_request_not_frozen(&request_data);
+
+            let mut new_request = Request {
+                status: RequestStatus::Cancelled,
+                ..request_data
             };
 
             self.requests.insert(&request_id, &new_request);
+            self.internal_update_status_index(&new_request, Some(&RequestStatus::Open));
+            self.internal_clear_applications(&request_id);
+
+            if new_request.escrow == EscrowStatus::Locked {
+                self.internal_release_bounty(&mut new_request);
+            }
+            if new_request.ft_escrow == EscrowStatus::Locked {
+                self.internal_release_ft_bounty(&mut new_request);
+            }
+
+            Some(new_request)
+        } else {
+            env::panic_str("No existe la solicitud a cancelar");
+        }
+    }
+
+    //a helper backs out, the request goes back to Open when nobody is left
+    pub fn withdraw_from_request(&mut self, request_id: u64) -> Option<Request> {
+        if let Some(request_data) = self.requests.get(&request_id) {
+            let helper_id = env::signer_account_id();
+            if !request_data.participants.iter().any(|participant| participant.account_id == helper_id && !participant.completed) {
+                env::panic_str("Solo quien atiende la solicitud puede abandonarla");
+            }
 
-            // Hacer el envió del swapti token
-            ext_nft::ft_transfer(
-                request_data.helper.unwrap(),
-                "1000000000000000000000000".to_string().clone(),
-                "dev-1678227171447-27568759288636".to_string().parse::<AccountId>().unwrap(),
-                NO_DEPOSIT,
-                MIN_GAS_FOR_NFT_TRANSFER_CALL
-            );
+            if request_data.status != RequestStatus::InProgress {
+                env::panic_str("La solicitud no está en progreso");
+            }
 
-            return Some(new_request);
+            let new_request = self.internal_remove_participant(request_data, &helper_id);
 
+            Some(new_request)
+        } else {
+            env::panic_str("No existe la solicitud a abandonar");
+        }
+    }
 
+    //the owner removes a helper that stopped responding, the request goes back to Open when nobody is left
+    pub fn release_helper(&mut self, request_id: u64, helper_id: AccountId) -> Option<Request> {
+        if let Some(request_data) = self.requests.get(&request_id) {
+            if request_data.owner_id != env::signer_account_id() {
+                env::panic_str("Solo el creador de la solicitud puede liberar a quien la atiende");
+            }
+
+            if request_data.status != RequestStatus::InProgress {
+                env::panic_str("La solicitud no está en progreso");
+            }
+
+            let participant = request_data.participants.iter()
+                .find(|participant| participant.account_id == helper_id && !participant.completed)
+                .unwrap_or_else(|| env::panic_str("La cuenta no atiende esta solicitud"));
+
+            if env::block_timestamp() < participant.joined_at.0 + RELEASE_HELPER_AFTER {
+                env::panic_str("Debes esperar 3 días desde que el ayudante se unió para liberarlo");
+            }
+
+            self.assert_request_not_frozen(&request_data);
+
+            let new_request = self.internal_remove_participant(request_data, &helper_id);
+
+            Some(new_request)
         } else {
-            env::panic_str("No existe la solicitud a atender");
+            env::panic_str("No existe la solicitud a liberar");
+        }
+    }
+
+    /*
+        The owner of the request, or the contract owner, removes an open or cancelled request from
+        every index. Applicants get their deposits back and the owner gets back the storage deposit.
+        Requests with a bounty still held or on its way must be cancelled and settled first, and requests
+        with credited helpers are kept.
+    */
+    pub fn delete_request(&mut self, request_id: u64) -> String {
+        if let Some(request_data) = self.requests.get(&request_id) {
+            if request_data.owner_id != env::signer_account_id() && env::predecessor_account_id() != self.owner_id {
+                env::panic_str("Solo el creador de la solicitud o el dueño del contrato pueden eliminarla");
+            }
+
+            if request_data.status != RequestStatus::Open && request_data.status != RequestStatus::Cancelled {
+                env::panic_str("Solo se pueden eliminar solicitudes abiertas o canceladas");
+            }
+
+            //frozen requests are kept until the owner is allowed back
+            self.assert_request_not_frozen(&request_data);
+
+            //the helpers credited for the request keep it in their history
+            if request_data.participants.iter().any(|participant| participant.completed) {
+                env::panic_str("La solicitud tiene ayudantes acreditados y no se puede eliminar");
+            }
+
+            let escrow_settled = |escrow: &EscrowStatus| *escrow == EscrowStatus::NoBounty || *escrow == EscrowStatus::Refunded;
+            if !escrow_settled(&request_data.escrow) || !escrow_settled(&request_data.ft_escrow) {
+                env::panic_str("La solicitud tiene una recompensa pendiente, cancélala y espera la devolución");
+            }
+
+            self.internal_remove_request(&request_data);
+
+            if request_data.storage_deposit.0 > 0 {
+                Promise::new(request_data.owner_id.clone()).transfer(request_data.storage_deposit.0);
+            }
+
+            "Solicitud eliminada con éxito".to_string()
+        } else {
+            env::panic_str("No existe la solicitud a eliminar");
+        }
+    }
+
+    /*
+        Anyone can clean up a timed out request. The helpers of an in progress request that ran out of time
+        since they joined are removed, and the request goes back to Open when none of the others is still
+        working on it. Once the deadline passed the request is Expired and its bounties go back to the owner.
+    */
+    pub fn expire_request(&mut self, request_id: u64) -> Option<Request> {
+        if let Some(request_data) = self.requests.get(&request_id) {
+            let mut new_request = match request_data.status {
+                RequestStatus::Open if self.internal_deadline_passed(&request_data) => request_data,
+                RequestStatus::InProgress => {
+                    let working = request_data.participants.iter().filter(|participant| !participant.completed).count();
+                    let timed_out: Vec<AccountId> = request_data.participants.iter()
+                        .filter(|participant| !participant.completed)
+                        .filter(|participant| env::block_timestamp() >= participant.joined_at.0 + request_data.work_timeout.0)
+                        .map(|participant| participant.account_id.clone())
+                        .collect();
+
+                    if timed_out.is_empty() {
+                        env::panic_str("La solicitud aún no vence");
+                    }
+
+                    if timed_out.len() == working {
+                        self.internal_reopen_request(request_data)
+                    } else {
+                        let mut request = request_data;
+                        for helper_id in timed_out.iter() {
+                            request = self.internal_remove_participant(request, helper_id);
+                        }
+                        request
+                    }
+                }
+                _ => env::panic_str("La solicitud aún no vence"),
+            };
+
+            if new_request.status == RequestStatus::Open && self.internal_deadline_passed(&new_request) {
+                new_request.status = RequestStatus::Expired;
+                self.requests.insert(&request_id, &new_request);
+                self.internal_update_status_index(&new_request, Some(&RequestStatus::Open));
+                self.internal_clear_applications(&request_id);
+
+                if new_request.escrow == EscrowStatus::Locked {
+                    self.internal_release_bounty(&mut new_request);
+                }
+                if new_request.ft_escrow == EscrowStatus::Locked {
+                    self.internal_release_ft_bounty(&mut new_request);
+                }
+            }
+
+            let expire_log: EventLog = EventLog {
+                standard: SWAPTI_STANDARD_NAME.to_string(),
+                version: SWAPTI_EVENT_SPEC.to_string(),
+                event: EventLogVariant::RequestExpired(vec![RequestExpiredLog {
+                    request_id,
+                    status: format!("{:?}", new_request.status),
+                }]),
+            };
+            env::log_str(&expire_log.to_string());
+
+            Some(new_request)
+        } else {
+            env::panic_str("No existe la solicitud a vencer");
         }
     }
-    
 }
 
 #[cfg(test)]
@@ -0,0 +1,192 @@
+use crate::*;
+
+//max length (in characters) of a message in a request thread
+pub const MAX_MESSAGE_LEN: usize = 500;
+//max number of messages in the thread of a request
+pub const MAX_MESSAGES_PER_REQUEST: u64 = 100;
+//part of the thread only the owner and the helpers can fill, so applicants can't use it up
+pub const RESERVED_MESSAGES_PER_REQUEST: u64 = 50;
+//max number of messages each pending applicant can write in a thread
+pub const MAX_MESSAGES_PER_APPLICANT: u64 = 10;
+
+//a message in the thread of a request
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Message {
+    pub author_id: AccountId,
+    pub body: String,
+    pub sent_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /*
+        The owner, the helpers and the pending applicants of a request can write in its thread.
+        Applicants write a few messages each and never in the part of the thread reserved for the owner
+        and the helpers. The author pays for the storage of the message.
+    */
+    #[payable]
+    pub fn post_message(&mut self, request_id: u64, body: String) -> Message {
+        let request = self.requests.get(&request_id)
+            .unwrap_or_else(|| env::panic_str("No existe la solicitud"));
+        self.assert_request_not_frozen(&request);
+        let author_id = env::signer_account_id();
+
+        let is_member = request.owner_id == author_id
+            || request.participants.iter().any(|participant| participant.account_id == author_id);
+        let is_applicant = !is_member && self.applications_per_request.get(&request_id)
+            .is_some_and(|applications| applications.get(&author_id).is_some());
+        if !is_member && !is_applicant {
+            env::panic_str("Solo el creador, quienes atienden la solicitud o quienes se postularon pueden escribir");
+        }
+
+        if body.is_empty() || body.chars().count() > MAX_MESSAGE_LEN {
+            env::panic_str(&format!("El mensaje debe tener entre 1 y {} caracteres", MAX_MESSAGE_LEN));
+        }
+
+        let initial_storage_usage = env::storage_usage();
+
+        let mut messages = self.messages_per_request.get(&request_id).unwrap_or_else(|| {
+            Vector::new(
+                StorageKey::MessagesPerRequestInner { request_id }
+                    .try_to_vec()
+                    .unwrap(),
+            )
+        });
+        if messages.len() >= MAX_MESSAGES_PER_REQUEST {
+            env::panic_str(&format!("El hilo de una solicitud admite hasta {} mensajes", MAX_MESSAGES_PER_REQUEST));
+        }
+        if is_applicant {
+            if messages.len() >= MAX_MESSAGES_PER_REQUEST - RESERVED_MESSAGES_PER_REQUEST {
+                env::panic_str("El resto del hilo queda para el creador y quienes atienden la solicitud");
+            }
+            let sent = messages.iter().filter(|message| message.author_id == author_id).count() as u64;
+            if sent >= MAX_MESSAGES_PER_APPLICANT {
+                env::panic_str(&format!("Cada postulante puede escribir hasta {} mensajes", MAX_MESSAGES_PER_APPLICANT));
+            }
+        }
+        let message = Message {
+            author_id,
+            body,
+            sent_at: U64(env::block_timestamp()),
+        };
+        messages.push(&message);
+        self.messages_per_request.insert(&request_id, &messages);
+
+        //refund any excess storage if the user attached too much. Panic if they didn't attach enough to cover the required.
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        message
+    }
+
+    //messages of a request, oldest first
+    pub fn get_request_messages(
+        &self,
+        request_id: u64,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Message> {
+        let messages = if let Some(messages) = self.messages_per_request.get(&request_id) {
+            messages
+        } else {
+            return vec![];
+        };
+
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        messages.iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{accounts, get_created_receipts};
+    use near_sdk::testing_env;
+
+    #[test]
+    fn test_request_message_thread() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        create_request_as(&mut context, &mut contract, accounts(1), "Necesito ayuda con Rust");
+        apply_to_request(&mut context, &mut contract, accounts(2), 0);
+
+        // an applicant asks a question before being picked
+        testing_env!(context.block_timestamp(5).build());
+        set_caller_with_deposit(&mut context, accounts(2), MINT_STORAGE_COST);
+        contract.post_message(0, "¿Qué versión de Rust usas?".to_string());
+
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        contract.post_message(0, "La última estable".to_string());
+
+        let messages = contract.get_request_messages(0, None, None);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].author_id, accounts(2));
+        assert_eq!(messages[0].sent_at, U64(5));
+        assert_eq!(messages[1].body, "La última estable".to_string());
+        assert_eq!(contract.get_request_messages(0, Some(U128(1)), Some(1))[0].author_id, accounts(1));
+    }
+
+    #[test]
+    fn test_deleted_request_refunds_message_authors() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        create_request_as(&mut context, &mut contract, accounts(1), "Necesito ayuda con Rust");
+        apply_to_request(&mut context, &mut contract, accounts(2), 0);
+        set_caller_with_deposit(&mut context, accounts(2), MINT_STORAGE_COST);
+        contract.post_message(0, "¿Qué versión de Rust usas?".to_string());
+
+        set_caller_with_deposit(&mut context, accounts(1), 0);
+        contract.cancel_request(0);
+        contract.delete_request(0);
+        let refunded_to_author = get_created_receipts().iter().any(|receipt| {
+            receipt.receiver_id == accounts(2)
+                && receipt.actions.iter().any(|action| matches!(action, VmAction::Transfer { deposit } if *deposit > 0))
+        });
+        assert!(refunded_to_author);
+        assert!(contract.get_request_messages(0, None, None).is_empty());
+    }
+
+    #[test]
+    fn test_applicants_cannot_use_up_the_thread() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        create_request_as(&mut context, &mut contract, accounts(1), "Necesito ayuda con Rust");
+        let applicants = [accounts(2), accounts(3), accounts(4), accounts(5), accounts(0)];
+        for applicant in applicants.iter() {
+            apply_to_request(&mut context, &mut contract, applicant.clone(), 0);
+        }
+
+        for _ in 0..MAX_MESSAGES_PER_APPLICANT {
+            set_caller_with_deposit(&mut context, accounts(2), MINT_STORAGE_COST);
+            contract.post_message(0, "¿Sigue abierta?".to_string());
+        }
+        assert_contract_panics(module_path!(), "test_applicants_cannot_use_up_the_thread", "hasta 10 mensajes", || {
+            contract.post_message(0, "¿Sigue abierta?".to_string());
+        });
+
+        for applicant in applicants.iter().skip(1) {
+            for _ in 0..MAX_MESSAGES_PER_APPLICANT {
+                set_caller_with_deposit(&mut context, applicant.clone(), MINT_STORAGE_COST);
+                contract.post_message(0, "Puedo ayudarte".to_string());
+            }
+        }
+        assert_eq!(
+            contract.get_request_messages(0, None, Some(MAX_MESSAGES_PER_REQUEST)).len() as u64,
+            MAX_MESSAGES_PER_REQUEST - RESERVED_MESSAGES_PER_REQUEST
+        );
+
+        // the reserved part is left for the owner
+        set_caller_with_deposit(&mut context, accounts(3), MINT_STORAGE_COST);
+        assert_contract_panics(module_path!(), "test_applicants_cannot_use_up_the_thread", "queda para el creador", || {
+            contract.post_message(0, "Puedo ayudarte".to_string());
+        });
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        contract.post_message(0, "Gracias a todos".to_string());
+    }
+}
@@ -62,6 +62,8 @@ pub struct JsonToken {
     pub approved_account_ids: HashMap<AccountId, u64>,
     //keep track of the royalty percentages for the token in a hash map
     pub royalty: HashMap<AccountId, u32>,
+    //whether the token is bound to its owner and can't be transferred
+    pub soulbound: bool,
 }
 
 pub trait NonFungibleTokenMetadata {
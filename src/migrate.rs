@@ -0,0 +1,406 @@
+use crate::*;
+
+//layout of a request as it is stored by the previous version
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OldRequest {
+    request_id: u64,
+    owner_id: AccountId,
+    description: String,
+    helper: Option<AccountId>,
+    status: RequestStatus,
+    review_owner: Option<Review>,
+    review_helper: Option<Review>,
+}
+
+//layout of the contract state as it is stored by the previous version
+#[derive(BorshDeserialize)]
+pub struct OldContract {
+    pub owner_id: AccountId,
+    pub tokens_per_owner: LookupMap<AccountId, UnorderedSet<TokenId>>,
+    pub tokens_by_id: LookupMap<TokenId, Token>,
+    pub token_metadata_by_id: UnorderedMap<TokenId, TokenMetadata>,
+    pub metadata: LazyOption<NFTContractMetadata>,
+    //written under the storage keys of tokens_per_owner, the requests are indexed again by migrate_requests
+    pub _requests_per_owner: LookupMap<AccountId, UnorderedSet<u64>>,
+    pub requests: UnorderedMap<u64, OldRequest>,
+    pub profiles: HashMap<AccountId, ProfileV1>,
+    pub profile_times_helped: HashMap<AccountId, TimesHelped>,
+}
+
+//converts a stored request to the current layout
+fn migrate_request(old_request: OldRequest) -> Request {
+    //requests that were already finished get a full review window from now on
+    let completed_at = if old_request.status == RequestStatus::Complete {
+        Some(U64(env::block_timestamp()))
+    } else {
+        None
+    };
+
+    let participants = old_request.helper.iter()
+        .map(|helper_id| Participant {
+            account_id: helper_id.clone(),
+            joined_at: U64(env::block_timestamp()),
+            completed: old_request.status == RequestStatus::Complete,
+            commitment_owner: None,
+            commitment_helper: None,
+            review_owner: old_request.review_owner.clone(),
+            review_helper: old_request.review_helper.clone(),
+        })
+        .collect();
+
+    Request {
+        request_id: old_request.request_id,
+        owner_id: old_request.owner_id,
+        description: old_request.description,
+        helper: old_request.helper,
+        //requests in progress start counting from the migration
+        attended_at: if old_request.status == RequestStatus::InProgress { Some(U64(env::block_timestamp())) } else { None },
+        status: old_request.status,
+        completed_at,
+        bounty: U128(0),
+        escrow: EscrowStatus::NoBounty,
+        ft_token_id: None,
+        ft_bounty: U128(0),
+        ft_escrow: EscrowStatus::NoBounty,
+        dispute: None,
+        deadline: None,
+        work_timeout: U64(DEFAULT_WORK_TIMEOUT),
+        //the creation time was not stored before, requests count as created at the migration
+        created_at: U64(env::block_timestamp()),
+        tags: vec![],
+        //requests created before storage was charged have nothing to refund
+        storage_deposit: U128(0),
+        capacity: 1,
+        participants,
+    }
+}
+
+impl Contract {
+    /*
+        The previous version indexed the requests of an account in the same storage as its tokens. Drops every
+        element of the shared set and adds back the ones that are tokens of the account.
+    */
+    pub(crate) fn internal_repair_token_set(&mut self, account_id: &AccountId) {
+        let shared_set = if let Some(shared_set) = self.tokens_per_owner.get(account_id) {
+            shared_set
+        } else {
+            return;
+        };
+        let prefix = StorageKey::TokenPerOwnerInner {
+            account_id_hash: hash_account_id(account_id),
+        }
+        .try_to_vec()
+        .unwrap();
+
+        //UnorderedSet keeps the elements under `e` by position and the position of each element under `i`
+        let mut token_ids: Vec<TokenId> = vec![];
+        for index in 0..shared_set.len() {
+            let element_key = [prefix.as_slice(), b"e", &index.to_le_bytes()].concat();
+            let raw_element = if let Some(raw_element) = env::storage_read(&element_key) {
+                raw_element
+            } else {
+                continue;
+            };
+            env::storage_remove(&[prefix.as_slice(), b"i", raw_element.as_slice()].concat());
+            env::storage_remove(&element_key);
+
+            if let Ok(token_id) = TokenId::try_from_slice(&raw_element) {
+                if self.tokens_by_id.get(&token_id).is_some_and(|token| &token.owner_id == account_id) {
+                    token_ids.push(token_id);
+                }
+            }
+        }
+
+        self.tokens_per_owner.remove(account_id);
+        for token_id in token_ids.iter() {
+            self.internal_add_token_to_owner(account_id, token_id);
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    //reads the state of the previous version and rebuilds it with the new collections
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let old_state: OldContract = env::state_read().expect("No hay estado previo que migrar");
+
+        //the old version never removed requests, so ids go from 0 to the number of requests
+        let next_request_id = old_state.requests.len();
+
+        let mut this = Self {
+            owner_id: old_state.owner_id,
+            tokens_per_owner: old_state.tokens_per_owner,
+            tokens_by_id: old_state.tokens_by_id,
+            token_metadata_by_id: old_state.token_metadata_by_id,
+            metadata: old_state.metadata,
+            //the requests are converted and indexed in batches with migrate_requests
+            requests_per_owner: LookupMap::new(StorageKey::RequestsPerOwner.try_to_vec().unwrap()),
+            requests: UnorderedMap::new(StorageKey::Requests.try_to_vec().unwrap()),
+            legacy_requests: old_state.requests,
+            //the profiles are moved to their own collections in batches with migrate_profiles
+            profiles: UnorderedMap::new(StorageKey::Profiles.try_to_vec().unwrap()),
+            profile_times_helped: LookupMap::new(StorageKey::ProfileTimesHelped.try_to_vec().unwrap()),
+            helped_requests_per_helper: LookupMap::new(
+                StorageKey::HelpedRequestsPerHelper.try_to_vec().unwrap(),
+            ),
+            reviews_per_account: LookupMap::new(StorageKey::ReviewsPerAccount.try_to_vec().unwrap()),
+            reputations: LookupMap::new(StorageKey::Reputations.try_to_vec().unwrap()),
+            applications_per_request: LookupMap::new(
+                StorageKey::ApplicationsPerRequest.try_to_vec().unwrap(),
+            ),
+            ft_token_whitelist: UnorderedSet::new(StorageKey::FtTokenWhitelist.try_to_vec().unwrap()),
+            arbiters: UnorderedSet::new(StorageKey::Arbiters.try_to_vec().unwrap()),
+            requests_per_helper: LookupMap::new(StorageKey::RequestsPerHelper.try_to_vec().unwrap()),
+            requests_per_status: LookupMap::new(StorageKey::RequestsPerStatus.try_to_vec().unwrap()),
+            tag_registry: UnorderedSet::new(StorageKey::TagRegistry.try_to_vec().unwrap()),
+            requests_per_tag: LookupMap::new(StorageKey::RequestsPerTag.try_to_vec().unwrap()),
+            next_request_id,
+            revisions_per_request: LookupMap::new(StorageKey::RevisionsPerRequest.try_to_vec().unwrap()),
+            messages_per_request: LookupMap::new(StorageKey::MessagesPerRequest.try_to_vec().unwrap()),
+            legacy_profiles: old_state.profiles,
+            legacy_profile_times_helped: old_state.profile_times_helped,
+            //profiles created before the deposit was charged have nothing to refund
+            profile_storage_deposits: LookupMap::new(StorageKey::ProfileStorageDeposits.try_to_vec().unwrap()),
+            contacts_per_request: LookupMap::new(StorageKey::ContactsPerRequest.try_to_vec().unwrap()),
+            request_storage_balances: LookupMap::new(StorageKey::RequestStorageBalances.try_to_vec().unwrap()),
+            skill_registry: UnorderedSet::new(StorageKey::SkillRegistry.try_to_vec().unwrap()),
+            //profiles of the first version have no skills
+            accounts_per_skill: LookupMap::new(StorageKey::AccountsPerSkill.try_to_vec().unwrap()),
+            //nobody is banned until the contract owner says so
+            access_mode: AccessMode::Blocklist,
+            access_list: UnorderedSet::new(StorageKey::AccessList.try_to_vec().unwrap()),
+            avatars: LookupMap::new(StorageKey::Avatars.try_to_vec().unwrap()),
+            badge_tiers: UnorderedMap::new(StorageKey::BadgeTiers.try_to_vec().unwrap()),
+            //badges minted so far are read from the help counters
+            claimed_badges: LookupMap::new(StorageKey::ClaimedBadges.try_to_vec().unwrap()),
+            //badges minted so far are flagged by migrate_soulbound_badges
+            soulbound_tokens: UnorderedSet::new(StorageKey::SoulboundTokens.try_to_vec().unwrap()),
+            //accounts with a profile from before are added when they delete it
+            onboarded: LookupSet::new(StorageKey::Onboarded.try_to_vec().unwrap()),
+            //profiles are added as migrate_profiles moves them
+            profiles_by_helped: TreeMap::new(StorageKey::ProfilesByHelped.try_to_vec().unwrap()),
+            profiles_by_helped_per_skill: LookupMap::new(StorageKey::ProfilesByHelpedPerSkill.try_to_vec().unwrap()),
+        };
+
+        for tier in default_badge_tiers() {
+            this.badge_tiers.insert(&tier.tier_id, &tier);
+        }
+
+        this
+    }
+
+    /*
+        Converts up to `limit` requests of the previous version to the current layout, rebuilding the owner, helper
+        and status indexes and the reputation counters from them. Requests show up as they are moved, call it right
+        after migrate until it returns 0, the number of requests still waiting to be moved.
+    */
+    #[private]
+    pub fn migrate_requests(&mut self, limit: u64) -> u64 {
+        let request_ids: Vec<u64> = self.legacy_requests.keys().take(limit as usize).collect();
+        let mut repaired_owners: Vec<AccountId> = vec![];
+        for request_id in request_ids {
+            let old_request = self.legacy_requests.remove(&request_id).unwrap();
+
+            //the token set of the owner also held their requests
+            if !repaired_owners.contains(&old_request.owner_id) {
+                self.internal_repair_token_set(&old_request.owner_id);
+                repaired_owners.push(old_request.owner_id.clone());
+            }
+
+            if let Some(helper_id) = old_request.helper.as_ref() {
+                self.internal_update_reputation(helper_id, |reputation| reputation.requests_attended += 1);
+                self.internal_add_request_to_helper(helper_id, &request_id);
+                //the helper gets the help counted like any finished request, the SWAPTI reward is not sent again
+                if old_request.status == RequestStatus::Complete {
+                    self.internal_credit_helper(helper_id, &request_id);
+                }
+
+                //reviews of the previous version were already public
+                if let Some(review) = old_request.review_owner.as_ref() {
+                    self.internal_publish_review(&request_id, helper_id, review, true);
+                }
+                if let Some(review) = old_request.review_helper.as_ref() {
+                    self.internal_publish_review(&request_id, &old_request.owner_id, review, false);
+                }
+            }
+
+            let request = migrate_request(old_request);
+            self.requests.insert(&request_id, &request);
+            self.internal_add_request_to_owner(&request.owner_id, &request_id);
+            self.internal_update_status_index(&request, None);
+        }
+
+        self.legacy_requests.len()
+    }
+
+    /*
+        Moves up to `limit` profiles and help counters from the legacy maps in the root state to their
+        own collections. Call it until it returns 0, the number of entries still waiting to be moved.
+    */
+    #[private]
+    pub fn migrate_profiles(&mut self, limit: u64) -> u64 {
+        let account_ids: Vec<AccountId> = self.legacy_profiles.keys().take(limit as usize).cloned().collect();
+        for account_id in account_ids {
+            let mut profile = self.legacy_profiles.remove(&account_id).unwrap();
+            //plaintext emails are dropped, accounts share encrypted contact details with share_contact
+            profile.email = String::new();
+            //an entry written after the upgrade is newer than the legacy one
+            if self.profiles.get(&account_id).is_none() {
+                self.profiles.insert(&account_id, &VersionedProfile::V1(profile));
+                //profiles of the first version have no skills
+                let times_helped = self.internal_get_times_helped(&account_id).unwrap_or_default().number;
+                self.internal_add_to_helped_index(&account_id, &[], times_helped);
+            }
+        }
+
+        let account_ids: Vec<AccountId> =
+            self.legacy_profile_times_helped.keys().take(limit as usize).cloned().collect();
+        for account_id in account_ids {
+            let times_helped = self.legacy_profile_times_helped.remove(&account_id).unwrap();
+            if self.profile_times_helped.get(&account_id).is_none() {
+                self.profile_times_helped.insert(&account_id, &times_helped);
+            }
+        }
+
+        (self.legacy_profiles.len() + self.legacy_profile_times_helped.len()) as u64
+    }
+
+    /*
+        Flags the bronze, silver and gold badges minted before tokens could be soulbound, going through up to `limit`
+        tokens starting at `from_index`. Call it with the returned index until it reaches nft_total_supply.
+    */
+    #[private]
+    pub fn migrate_soulbound_badges(&mut self, from_index: u64, limit: u64) -> u64 {
+        let token_ids = self.token_metadata_by_id.keys_as_vector();
+        let end = std::cmp::min(from_index.saturating_add(limit), token_ids.len());
+        for index in from_index..end {
+            let token_id = token_ids.get(index).unwrap();
+            let media = self.token_metadata_by_id.get(&token_id).and_then(|metadata| metadata.media);
+            if media.is_some_and(|media| [BRONCE, PLATA, ORO].contains(&media.as_str())) {
+                self.soulbound_tokens.insert(&token_id);
+            }
+        }
+
+        end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn test_migrate_profiles_in_batches() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.current_account_id(accounts(0)).build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        // state left by the previous version
+        for account_id in [accounts(1), accounts(2), accounts(3)] {
+            contract.legacy_profiles.insert(
+                account_id.clone(),
+                ProfileV1 { email: "ana@swapti.com".to_string(), bio: "Rust".to_string() },
+            );
+            contract.legacy_profile_times_helped.insert(
+                account_id,
+                TimesHelped { number: 4, bronze: false, silver: false, gold: false },
+            );
+        }
+
+        assert_eq!(contract.migrate_profiles(2), 2);
+        // profiles still waiting to be moved can be read
+        assert_eq!(contract.get_profile(accounts(3)).profile.bio, "Rust".to_string());
+        assert_eq!(contract.get_number_swaps(accounts(3)).number, 4);
+
+        assert_eq!(contract.migrate_profiles(2), 0);
+        assert!(contract.legacy_profiles.is_empty());
+        assert_eq!(contract.profiles.len(), 3);
+        assert_eq!(contract.get_number_swaps(accounts(1)).number, 4);
+    }
+
+    #[test]
+    fn test_migrate_soulbound_badges_in_batches() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.current_account_id(accounts(0)).build());
+        let mut contract = Contract::new_default_meta(accounts(0));
+
+        // a badge and a regular token minted by the previous version
+        for (token_id, media) in [("bronce-1", BRONCE), ("1", "QmOtro")] {
+            contract.internal_mint(
+                &token_id.to_string(),
+                &TokenMetadata { media: Some(media.to_string()), ..sample_token_metadata() },
+                &accounts(1),
+            );
+        }
+
+        assert_eq!(contract.migrate_soulbound_badges(0, 1), 1);
+        assert_eq!(contract.migrate_soulbound_badges(1, 10), 2);
+        assert!(contract.soulbound_tokens.contains(&"bronce-1".to_string()));
+        assert!(!contract.soulbound_tokens.contains(&"1".to_string()));
+    }
+
+    //stores a request the way the previous version did, indexed in the token set of its owner
+    fn insert_old_request(contract: &mut Contract, request_id: u64, owner_id: AccountId, helper: Option<AccountId>, status: RequestStatus) {
+        contract.legacy_requests.insert(&request_id, &OldRequest {
+            request_id,
+            owner_id: owner_id.clone(),
+            description: "Necesito ayuda con Rust".to_string(),
+            helper,
+            status,
+            review_owner: None,
+            review_helper: None,
+        });
+        contract.next_request_id = request_id + 1;
+
+        let mut shared_map: LookupMap<AccountId, UnorderedSet<u64>> =
+            LookupMap::new(StorageKey::TokensPerOwner.try_to_vec().unwrap());
+        let mut shared_set = shared_map.get(&owner_id).unwrap_or_else(|| {
+            UnorderedSet::new(
+                StorageKey::TokenPerOwnerInner { account_id_hash: hash_account_id(&owner_id) }
+                    .try_to_vec()
+                    .unwrap(),
+            )
+        });
+        shared_set.insert(&request_id);
+        shared_map.insert(&owner_id, &shared_set);
+    }
+
+    #[test]
+    fn test_migrate_requests_in_batches() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+
+        // tokens and requests of accounts(1) were written in the same set
+        contract.internal_mint(&"1".to_string(), &sample_token_metadata(), &accounts(1));
+        insert_old_request(&mut contract, 0, accounts(1), Some(accounts(2)), RequestStatus::Complete);
+        contract.internal_mint(&"2".to_string(), &sample_token_metadata(), &accounts(1));
+        insert_old_request(&mut contract, 1, accounts(1), Some(accounts(3)), RequestStatus::InProgress);
+        insert_old_request(&mut contract, 2, accounts(3), None, RequestStatus::Open);
+
+        assert_eq!(contract.migrate_requests(2), 1);
+        assert_eq!(contract.migrate_requests(2), 0);
+        assert!(contract.legacy_requests.is_empty());
+
+        let mut token_ids: Vec<TokenId> = contract.nft_tokens_for_owner(accounts(1), None, None)
+            .into_iter()
+            .map(|token| token.token_id)
+            .collect();
+        token_ids.sort();
+        assert_eq!(token_ids, vec!["1".to_string(), "2".to_string()]);
+        assert!(contract.nft_tokens_for_owner(accounts(3), None, None).is_empty());
+
+        assert_eq!(contract.request_for_owner(accounts(1), None, None).len(), 2);
+        assert_eq!(contract.requests_by_status(RequestStatus::Open, None, None)[0].request_id, 2);
+        assert_eq!(contract.requests_by_status(RequestStatus::Complete, None, None).len(), 1);
+        assert_eq!(contract.requests_for_helper(accounts(3), None, None, None).len(), 1);
+        assert_eq!(contract.get_reputation(accounts(2)).requests_completed, 1);
+        assert_eq!(contract.get_number_swaps(accounts(2)).number, 1);
+        assert_eq!(contract.helped_requests(accounts(2), None, None)[0].request_id, 0);
+        assert_eq!(contract.get_number_swaps(accounts(3)).number, 0);
+    }
+}
@@ -1,13 +1,10 @@
 use crate::*;
 use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::serde_json::{json,from_str};
-use near_sdk::{env,ext_contract, Balance,Gas, near_bindgen, AccountId, PromiseOrValue, PromiseResult, PanicOnDefault, log, BorshStorageKey, require};
-use near_sdk::json_types::{U128, U64};
-use near_sdk::Promise;
+use near_sdk::{env, near_bindgen, AccountId};
+use near_sdk::json_types::U128;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use std::collections::HashMap;
-use near_sdk::collections::{LazyOption, LookupMap, UnorderedMap, UnorderedSet};
 
+//IPFS CIDs of the tiers the contract starts with
 pub const BRONCE: &str = "QmT6tLkZMbqvDKXF8pCMMew8fFs17c2z98XGrmvBb2AJ9W";
 pub const PLATA: &str = "QmZ3at5KDTa8a1zhs3qAgkMpQ48gbWoN53ZLJw4A88WyK2";
 pub const ORO: &str = "Qmd1U8jL4eEn8gsBvQfhXmn2oaNfF9GwCWxQ3UEkccdZV9";
@@ -27,14 +24,60 @@ pub struct NewTokenMetadata {
     extra: String
 }
 
+//a badge helpers can mint once they helped enough people
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BadgeTier {
+    pub tier_id: String,
+    //number of people the account must have helped
+    pub threshold: u64,
+    pub title: String,
+    pub description: String,
+    //IPFS CID of the badge image
+    pub media: String,
+    pub media_hash: Option<Base64VecU8>,
+    //disabled tiers can't be minted, badges already minted are kept
+    pub enabled: bool,
+    //badges of the tier can't be transferred, so reputation can't be bought
+    #[serde(default)]
+    pub soulbound: bool,
+}
+
+//tiers of the former mint_bronce, mint_plata and mint_oro methods
+pub(crate) fn default_badge_tiers() -> Vec<BadgeTier> {
+    [("bronce", 10, "Insignia de Bronce", BRONCE), ("plata", 25, "Insignia de Plata", PLATA), ("oro", 50, "Insignia de ORO", ORO)]
+        .into_iter()
+        .map(|(tier_id, threshold, title, media)| BadgeTier {
+            tier_id: tier_id.to_string(),
+            threshold,
+            title: title.to_string(),
+            description: format!("Este NFT conmemora que haz ayudado a {} personas", threshold),
+            media: media.to_string(),
+            media_hash: None,
+            enabled: true,
+            soulbound: true,
+        })
+        .collect()
+}
+
 #[near_bindgen]
 impl Contract {
 
+    /*
+        Mints the badge of a tier to the caller once they helped at least as many people as the tier
+        asks for. Each account mints a tier only once and pays for the storage of the token.
+    */
     #[payable]
-    pub fn mint_bronce( &mut self) -> String {
+    pub fn mint_badge(&mut self, tier_id: String) -> String {
         let receiver_id = env::predecessor_account_id();
 
-        let profile_times_helped = self.profile_times_helped.get(&receiver_id);
+        let tier = self.badge_tiers.get(&tier_id)
+            .unwrap_or_else(|| env::panic_str("No existe la insignia"));
+        if !tier.enabled {
+            env::panic_str("La insignia no está disponible");
+        }
+
+        let profile_times_helped = self.internal_get_times_helped(&receiver_id);
         
         if profile_times_helped.is_none() {
             env::panic_str("Aún no has ayudado a ninguna persona");
@@ -42,25 +85,24 @@ impl Contract {
 
         let info = profile_times_helped.unwrap();
 
-        if info.number < 10 {
-            env::panic_str("Necesitas al menos ayudar a 10 personas");
+        if info.number < tier.threshold {
+            env::panic_str(&format!("Necesitas al menos ayudar a {} personas", tier.threshold));
         }
 
-        if info.bronze {
+        if self.internal_badge_claimed(&receiver_id, &tier_id) {
             env::panic_str("Ya minaste este token");
         }
 
-        let deposit = env::attached_deposit();
-        let mut new_token = TokenMetadata {
-            title:  Some("Insignia de Bronce".to_string()), 
-            description:  Some("Este NFT conmemora que haz ayudado a 10 personas".to_string()),
-            media:  Some("".to_string()),
+        let new_token = TokenMetadata {
+            title:  Some(tier.title), 
+            description:  Some(tier.description),
+            media:  Some(tier.media),
             expires_at: None,
             starts_at: None,
             copies: None,
             extra: None,
             issued_at: None,
-            media_hash: None,
+            media_hash: tier.media_hash,
             reference: None,
             reference_hash: None,
             updated_at: None
@@ -68,56 +110,15 @@ impl Contract {
 
         let initial_storage_usage = env::storage_usage();
 
-        new_token.media = Some(BRONCE.to_string());
         let token_id: TokenId = (self.token_metadata_by_id.len()).to_string();
+        self.internal_mint(&token_id, &new_token, &receiver_id);
 
-        // create a royalty map to store in the token
-        let mut royalty = HashMap::new();
-
-        //specify the token struct that contains the owner ID 
-        let token = Token {
-            //set the owner ID equal to the receiver ID passed into the function
-            owner_id: receiver_id,
-            //we set the approved account IDs to the default value (an empty map)
-            approved_account_ids: Default::default(),
-            //the next approval ID is set to 0
-            next_approval_id: 0,
-            //the map of perpetual royalties for the token (The owner will get 100% - total perpetual royalties)
-            royalty
-        };
-
-        //insert the token ID and token struct and make sure that the token doesn't exist
-        assert!(
-            self.tokens_by_id.insert(&token_id, &token).is_none(),
-            "Token already exists"
-        );
-
-        //insert the token ID and metadata
-        self.token_metadata_by_id.insert(&token_id, &new_token);
-
-        //call the internal method for adding the token to the owner
-        self.internal_add_token_to_owner(&token.owner_id, &token_id);
-
-
-        // Construct the mint log as per the events standard.
-        let nft_mint_log: EventLog = EventLog {
-            // Standard name ("nep171").
-            standard: NFT_STANDARD_NAME.to_string(),
-            // Version of the standard ("nft-1.0.0").
-            version: NFT_METADATA_SPEC.to_string(),
-            // The data related with the event stored in a vector.
-            event: EventLogVariant::NftMint(vec![NftMintLog {
-                // Owner of the token.
-                owner_id: token.owner_id.to_string(),
-                // Vector of token IDs that were minted.
-                token_ids: vec![token_id.to_string()],
-                // An optional memo to include.
-                memo: None,
-            }]),
-        };
+        if tier.soulbound {
+            self.soulbound_tokens.insert(&token_id);
+        }
 
-        // Log the serialized json.
-        env::log_str(&nft_mint_log.to_string());
+        //mark the badge as minted so it can't be claimed twice
+        self.internal_add_claimed_badge(&receiver_id, &tier_id);
 
         //calculate the required storage which was the used - initial
         let required_storage_in_bytes = env::storage_usage() - initial_storage_usage;
@@ -125,206 +126,125 @@ impl Contract {
         //refund any excess storage if the user attached too much. Panic if they didn't attach enough to cover the required.
         refund_deposit(required_storage_in_bytes);
     
-        return "El token fué minado con éxito".to_string();
+        "El token fué minado con éxito".to_string()
     }
 
-    #[payable]
-    pub fn mint_plata( &mut self) -> String {
-        // Agregar validación para verificar que ya ayudaste a minimo 25 personas
-
-        let receiver_id = env::predecessor_account_id();
+    //the contract owner adds a tier or replaces the one with the same id
+    pub fn set_badge_tier(&mut self, tier: BadgeTier) {
+        self.assert_contract_owner();
 
-        let profile_times_helped = self.profile_times_helped.get(&receiver_id);
-        
-        if profile_times_helped.is_none() {
-            env::panic_str("Aún no has ayudado a ninguna persona");
+        if tier.tier_id.is_empty() || tier.title.is_empty() || tier.media.is_empty() {
+            env::panic_str("La insignia necesita un id, un título y una imagen");
         }
-
-        let info = profile_times_helped.unwrap();
-
-        if info.number < 25 {
-            env::panic_str("Necesitas al menos ayudar a 25 personas");
+        if tier.threshold == 0 {
+            env::panic_str("La insignia debe pedir al menos una ayuda");
         }
 
-        if info.silver {
-            env::panic_str("Ya minaste este token");
-        }
-
-        let deposit = env::attached_deposit();
-        let mut new_token = TokenMetadata {
-            title:  Some("Insignia de Plata".to_string()), 
-            description:  Some("Este NFT conmemora que haz ayudado a 25 personas".to_string()),
-            media:  Some("".to_string()),
-            expires_at: None,
-            starts_at: None,
-            copies: None,
-            extra: None,
-            issued_at: None,
-            media_hash: None,
-            reference: None,
-            reference_hash: None,
-            updated_at: None
-        };
-
-        let initial_storage_usage = env::storage_usage();
-
-        new_token.media = Some(PLATA.to_string());
-        let token_id: TokenId = (self.token_metadata_by_id.len()).to_string();
-
-        // create a royalty map to store in the token
-        let mut royalty = HashMap::new();
-
-        //specify the token struct that contains the owner ID 
-        let token = Token {
-            //set the owner ID equal to the receiver ID passed into the function
-            owner_id: receiver_id,
-            //we set the approved account IDs to the default value (an empty map)
-            approved_account_ids: Default::default(),
-            //the next approval ID is set to 0
-            next_approval_id: 0,
-            //the map of perpetual royalties for the token (The owner will get 100% - total perpetual royalties)
-            royalty
-        };
-
-        //insert the token ID and token struct and make sure that the token doesn't exist
-        assert!(
-            self.tokens_by_id.insert(&token_id, &token).is_none(),
-            "Token already exists"
-        );
-
-        //insert the token ID and metadata
-        self.token_metadata_by_id.insert(&token_id, &new_token);
-
-        //call the internal method for adding the token to the owner
-        self.internal_add_token_to_owner(&token.owner_id, &token_id);
-
-
-        // Construct the mint log as per the events standard.
-        let nft_mint_log: EventLog = EventLog {
-            // Standard name ("nep171").
-            standard: NFT_STANDARD_NAME.to_string(),
-            // Version of the standard ("nft-1.0.0").
-            version: NFT_METADATA_SPEC.to_string(),
-            // The data related with the event stored in a vector.
-            event: EventLogVariant::NftMint(vec![NftMintLog {
-                // Owner of the token.
-                owner_id: token.owner_id.to_string(),
-                // Vector of token IDs that were minted.
-                token_ids: vec![token_id.to_string()],
-                // An optional memo to include.
-                memo: None,
-            }]),
-        };
-
-        // Log the serialized json.
-        env::log_str(&nft_mint_log.to_string());
-
-        //calculate the required storage which was the used - initial
-        let required_storage_in_bytes = env::storage_usage() - initial_storage_usage;
-
-        //refund any excess storage if the user attached too much. Panic if they didn't attach enough to cover the required.
-        refund_deposit(required_storage_in_bytes);
-    
-        return "El token fué minado con éxito".to_string();
+        self.badge_tiers.insert(&tier.tier_id, &tier);
     }
 
-    #[payable]
-    pub fn mint_oro( &mut self) -> String {
-        // Agregar validación para verificar que ya ayudaste a minimo 50 personas
-        let receiver_id = env::predecessor_account_id();
+    //tiers aren't removed so the badges already minted keep pointing to them
+    pub fn set_badge_tier_enabled(&mut self, tier_id: String, enabled: bool) {
+        self.assert_contract_owner();
 
-        let profile_times_helped = self.profile_times_helped.get(&receiver_id);
-        
-        if profile_times_helped.is_none() {
-            env::panic_str("Aún no has ayudado a ninguna persona");
-        }
+        let mut tier = self.badge_tiers.get(&tier_id)
+            .unwrap_or_else(|| env::panic_str("No existe la insignia"));
+        tier.enabled = enabled;
+        self.badge_tiers.insert(&tier_id, &tier);
+    }
 
-        let info = profile_times_helped.unwrap();
+    //the contract owner flags single tokens, such as badges minted before the tiers were soulbound
+    pub fn set_token_soulbound(&mut self, token_id: TokenId, soulbound: bool) {
+        self.assert_contract_owner();
 
-        if info.number < 50 {
-            env::panic_str("Necesitas al menos ayudar a 50 personas");
+        if self.tokens_by_id.get(&token_id).is_none() {
+            env::panic_str("No existe el token");
         }
 
-        if info.gold {
-            env::panic_str("Ya minaste este token");
+        if soulbound {
+            self.soulbound_tokens.insert(&token_id);
+        } else {
+            self.soulbound_tokens.remove(&token_id);
         }
+    }
 
-        let deposit = env::attached_deposit();
-        let mut new_token = TokenMetadata {
-            title:  Some("Insignia de ORO".to_string()), 
-            description:  Some("Este NFT conmemora que haz ayudado a 50 personas".to_string()),
-            media:  Some("".to_string()),
-            expires_at: None,
-            starts_at: None,
-            copies: None,
-            extra: None,
-            issued_at: None,
-            media_hash: None,
-            reference: None,
-            reference_hash: None,
-            updated_at: None
-        };
-
-        let initial_storage_usage = env::storage_usage();
-
-        new_token.media = Some(ORO.to_string());
-        let token_id: TokenId = (self.token_metadata_by_id.len()).to_string();
+    pub fn get_badge_tiers(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<BadgeTier> {
+        let start = u128::from(from_index.unwrap_or(U128(0)));
 
-        // create a royalty map to store in the token
-        let mut royalty = HashMap::new();
-
-        //specify the token struct that contains the owner ID 
-        let token = Token {
-            //set the owner ID equal to the receiver ID passed into the function
-            owner_id: receiver_id,
-            //we set the approved account IDs to the default value (an empty map)
-            approved_account_ids: Default::default(),
-            //the next approval ID is set to 0
-            next_approval_id: 0,
-            //the map of perpetual royalties for the token (The owner will get 100% - total perpetual royalties)
-            royalty
-        };
-
-        //insert the token ID and token struct and make sure that the token doesn't exist
-        assert!(
-            self.tokens_by_id.insert(&token_id, &token).is_none(),
-            "Token already exists"
-        );
-
-        //insert the token ID and metadata
-        self.token_metadata_by_id.insert(&token_id, &new_token);
-
-        //call the internal method for adding the token to the owner
-        self.internal_add_token_to_owner(&token.owner_id, &token_id);
-
-
-        // Construct the mint log as per the events standard.
-        let nft_mint_log: EventLog = EventLog {
-            // Standard name ("nep171").
-            standard: NFT_STANDARD_NAME.to_string(),
-            // Version of the standard ("nft-1.0.0").
-            version: NFT_METADATA_SPEC.to_string(),
-            // The data related with the event stored in a vector.
-            event: EventLogVariant::NftMint(vec![NftMintLog {
-                // Owner of the token.
-                owner_id: token.owner_id.to_string(),
-                // Vector of token IDs that were minted.
-                token_ids: vec![token_id.to_string()],
-                // An optional memo to include.
-                memo: None,
-            }]),
-        };
+        self.badge_tiers.values()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
 
-        // Log the serialized json.
-        env::log_str(&nft_mint_log.to_string());
+    //ids of the tiers the account already minted
+    pub fn claimed_badges(&self, account_id: AccountId) -> Vec<String> {
+        self.badge_tiers.keys()
+            .filter(|tier_id| self.internal_badge_claimed(&account_id, tier_id))
+            .collect()
+    }
 
-        //calculate the required storage which was the used - initial
-        let required_storage_in_bytes = env::storage_usage() - initial_storage_usage;
+}
 
-        //refund any excess storage if the user attached too much. Panic if they didn't attach enough to cover the required.
-        refund_deposit(required_storage_in_bytes);
-    
-        return "El token fué minado con éxito".to_string();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+
+    #[test]
+    fn test_mint_badge_from_tier_registry() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        assert_eq!(contract.get_badge_tiers(None, None).len(), 3);
+
+        // bronze was minted with the old method, it still counts as claimed
+        contract.internal_update_times_helped(&accounts(1), |times_helped| {
+            times_helped.number = 12;
+            times_helped.bronze = true;
+        });
+        assert_eq!(contract.claimed_badges(accounts(1)), vec!["bronce".to_string()]);
+
+        set_caller(&mut context, accounts(0));
+        contract.set_badge_tier(BadgeTier {
+            tier_id: "aprendiz".to_string(),
+            threshold: 11,
+            title: "Insignia de Aprendiz".to_string(),
+            description: "Este NFT conmemora que haz ayudado a 11 personas".to_string(),
+            media: "QmAprendiz".to_string(),
+            media_hash: None,
+            enabled: true,
+            soulbound: false,
+        });
+
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        contract.mint_badge("aprendiz".to_string());
+
+        let tokens = contract.nft_tokens_for_owner(accounts(1), None, None);
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].metadata.title, Some("Insignia de Aprendiz".to_string()));
+        assert_eq!(tokens[0].metadata.media, Some("QmAprendiz".to_string()));
+        let mut claimed = contract.claimed_badges(accounts(1));
+        claimed.sort();
+        assert_eq!(claimed, vec!["aprendiz".to_string(), "bronce".to_string()]);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_badges_of_default_tiers_are_soulbound() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        contract.internal_update_times_helped(&accounts(1), |times_helped| times_helped.number = 10);
+
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        contract.mint_badge("bronce".to_string());
+        let token_id = contract.nft_tokens_for_owner(accounts(1), None, None)[0].token_id.clone();
+        let token = crate::nft_core::NonFungibleTokenCore::nft_token(&contract, token_id.clone()).unwrap();
+        assert!(token.soulbound);
+
+        // the contract owner can release a single token
+        set_caller(&mut context, accounts(0));
+        contract.set_token_soulbound(token_id.clone(), false);
+        let token = crate::nft_core::NonFungibleTokenCore::nft_token(&contract, token_id).unwrap();
+        assert!(!token.soulbound);
+    }
+}
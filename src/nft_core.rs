@@ -1,3 +1,5 @@
+//the generated ext_self::nft_resolve_transfer takes the receiver, deposit and gas on top of its own arguments
+#![allow(clippy::too_many_arguments)]
 use crate::*;
 use near_sdk::{ext_contract, Gas, PromiseResult};
 
@@ -46,12 +48,6 @@ trait NonFungibleTokenReceiver {
     ) -> Promise;
 }
 
-#[ext_contract(ext_nft)]
-pub trait ExternsContract {
-    fn mint_swapti(&self,account_id: AccountId, amount: String) -> String;
-    fn ft_transfer(&self,receiver_id: AccountId, amount: String) -> String;
-}
-
 #[ext_contract(ext_self)]
 trait NonFungibleTokenResolver {
     /*
@@ -85,7 +81,7 @@ trait NonFungibleTokenResolver {
     this is stored on THIS contract and is meant to analyze what happened in the cross contract call when nft_on_transfer was called
     as part of the nft_transfer_call method
 */ 
-trait NonFungibleTokenResolver {
+pub trait NonFungibleTokenResolver {
     fn nft_resolve_transfer(
         &mut self,
         //we introduce an authorized ID for logging the transfer event
@@ -212,6 +208,7 @@ impl NonFungibleTokenCore for Contract {
         if let Some(token) = self.tokens_by_id.get(&token_id) {
             //we'll get the metadata for that token
             let metadata = self.token_metadata_by_id.get(&token_id).unwrap();
+            let soulbound = self.soulbound_tokens.contains(&token_id);
             //we return the JsonToken (wrapped by Some since we return an option)
             Some(JsonToken {
                 token_id,
@@ -219,6 +216,7 @@ impl NonFungibleTokenCore for Contract {
                 metadata,
                 approved_account_ids: token.approved_account_ids,
                 royalty: token.royalty,
+                soulbound,
             })
         } else { //if there wasn't a token ID in the tokens_by_id collection, we return None
             None
@@ -279,6 +277,8 @@ impl NonFungibleTokenResolver for Contract {
 
         //we remove the token from the receiver
         self.internal_remove_token_from_owner(&receiver_id.clone(), &token_id);
+        //the receiver may have pinned it as avatar before giving it back
+        self.internal_unpin_avatar(&receiver_id, &token_id);
         //we add the token to the original owner
         self.internal_add_token_to_owner(&owner_id, &token_id);
 
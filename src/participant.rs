@@ -0,0 +1,229 @@
+use crate::*;
+
+//max number of helpers a group request can take
+pub const MAX_CAPACITY: u64 = 20;
+
+//a helper taking part in a request
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Participant {
+    pub account_id: AccountId,
+    pub joined_at: U64,
+    //set once the helper was credited for the request
+    pub completed: bool,
+    //sha256 of the review the owner wrote about the helper, see review_commitment
+    pub commitment_owner: Option<Base64VecU8>,
+    //sha256 of the review the helper wrote about the owner
+    pub commitment_helper: Option<Base64VecU8>,
+    //review written by the owner about the helper, once it is revealed
+    pub review_owner: Option<Review>,
+    //review written by the helper about the owner, once it is revealed
+    pub review_helper: Option<Review>,
+}
+
+#[near_bindgen]
+impl Contract {
+    //the owner signs off a helper of a group request that already did their part, the rest are credited on finish
+    pub fn complete_participant(&mut self, request_id: u64, account_id: AccountId) -> Option<Request> {
+        if let Some(mut request_data) = self.requests.get(&request_id) {
+            if request_data.owner_id != env::signer_account_id() {
+                env::panic_str("Solo el creador de la solicitud puede finalizar la participación de un ayudante");
+            }
+
+            if request_data.status != RequestStatus::InProgress {
+                env::panic_str("La solicitud no está en progreso");
+            }
+
+            //a request with a single helper is signed off with finish_request
+            if request_data.capacity <= 1 {
+                env::panic_str("Solo se puede finalizar la participación de un ayudante en solicitudes grupales");
+            }
+
+            self.assert_request_not_frozen(&request_data);
+
+            let participant = request_data.participants.iter_mut()
+                .find(|participant| participant.account_id == account_id)
+                .unwrap_or_else(|| env::panic_str("La cuenta no participa en esta solicitud"));
+
+            if participant.completed {
+                env::panic_str("La participación de la cuenta ya fué finalizada");
+            }
+            participant.completed = true;
+
+            self.requests.insert(&request_id, &request_data);
+            self.internal_reward_helper(&account_id, &request_id);
+
+            Some(request_data)
+        } else {
+            env::panic_str("No existe la solicitud");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    //accounts(1) creates a request for two helpers and picks accounts(2) and accounts(3)
+    fn contract_with_full_group_request(context: &mut VMContextBuilder) -> Contract {
+        let mut contract = new_contract(context);
+        register(&mut contract, &accounts(1));
+        set_caller_with_deposit(context, accounts(1), MINT_STORAGE_COST);
+        contract.create_request("Revisar mi contrato en pareja".to_string(), None, None, None, None, Some(2));
+        apply_to_request(context, &mut contract, accounts(2), 0);
+        apply_to_request(context, &mut contract, accounts(3), 0);
+        set_caller(context, accounts(1));
+        contract.accept_application(0, accounts(2));
+        contract.accept_application(0, accounts(3));
+        contract
+    }
+
+    #[test]
+    fn test_group_request_credits_every_helper() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        register(&mut contract, &accounts(1));
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        contract.create_request("Enseñar NEAR a 5 personas".to_string(), None, None, None, None, Some(3));
+
+        apply_to_request(&mut context, &mut contract, accounts(2), 0);
+        apply_to_request(&mut context, &mut contract, accounts(3), 0);
+        apply_to_request(&mut context, &mut contract, accounts(4), 0);
+        set_caller(&mut context, accounts(1));
+        contract.accept_application(0, accounts(2));
+        contract.accept_application(0, accounts(3));
+
+        // a seat is still free, so the request keeps taking helpers while in progress
+        let request = contract.get_request(0).unwrap();
+        assert!(request.status == RequestStatus::InProgress);
+        assert_eq!(request.helper, Some(accounts(2)));
+        assert_eq!(contract.request_applications(0, None, None).len(), 1);
+
+        // one helper leaves, the other one is signed off early
+        set_caller(&mut context, accounts(2));
+        contract.withdraw_from_request(0);
+        set_caller(&mut context, accounts(1));
+        contract.complete_participant(0, accounts(3));
+        contract.accept_application(0, accounts(4));
+        contract.finish_request(0);
+
+        let request = contract.get_request(0).unwrap();
+        assert_eq!(request.helper, Some(accounts(3)));
+        assert_eq!(request.participants.len(), 2);
+        assert!(request.participants.iter().all(|participant| participant.completed));
+        assert_eq!(contract.get_number_swaps(accounts(3)).number, 1);
+        assert_eq!(contract.get_number_swaps(accounts(4)).number, 1);
+        assert_eq!(contract.get_number_swaps(accounts(2)).number, 0);
+        assert_eq!(contract.get_reputation(accounts(2)).abandoned, 1);
+    }
+
+    #[test]
+    fn test_every_helper_of_a_group_request_writes_and_reviews() {
+        let mut context = get_context(accounts(0));
+        let mut contract = contract_with_full_group_request(&mut context);
+
+        // the second helper writes in the thread too
+        set_caller_with_deposit(&mut context, accounts(3), MINT_STORAGE_COST);
+        contract.post_message(0, "Ya revisé mi parte".to_string());
+        testing_env!(context.attached_deposit(0).build());
+
+        set_caller(&mut context, accounts(1));
+        contract.finish_request(0);
+        commit_review(&mut contract, 0, 5, "Excelente ayuda", Some(accounts(2)));
+        commit_review(&mut contract, 0, 3, "Buena ayuda", Some(accounts(3)));
+        set_caller(&mut context, accounts(2));
+        commit_review(&mut contract, 0, 4, "Solicitud clara", None);
+        reveal_review(&mut contract, 0, 4, "Solicitud clara", None);
+
+        // each pair is revealed on its own
+        set_caller(&mut context, accounts(3));
+        commit_review(&mut contract, 0, 4, "Solicitud clara", None);
+        reveal_review(&mut contract, 0, 4, "Solicitud clara", None);
+        set_caller(&mut context, accounts(1));
+        reveal_review(&mut contract, 0, 3, "Buena ayuda", Some(accounts(3)));
+        assert_eq!(contract.reviews_for_account(accounts(1), None, None).len(), 2);
+        assert_eq!(contract.reviews_for_account(accounts(3), None, None)[0].rating, 3);
+        assert_eq!(contract.get_reputation(accounts(1)).avg_rating_as_requester, Some(400));
+    }
+
+    #[test]
+    fn test_group_request_with_every_seat_credited_is_completed() {
+        let mut context = get_context(accounts(0));
+        let mut contract = contract_with_full_group_request(&mut context);
+        contract.complete_participant(0, accounts(2));
+        contract.complete_participant(0, accounts(3));
+        contract.open_dispute(0, "Ya terminaron, pero la solicitud sigue abierta".to_string());
+        set_caller(&mut context, accounts(0));
+        contract.add_arbiter(accounts(4));
+
+        // the arbiter can't open the request again, every seat is taken
+        set_caller(&mut context, accounts(4));
+        contract.resolve_dispute(0, DisputeResolution::OwnerReopen);
+        assert!(contract.get_request(0).unwrap().status == RequestStatus::Complete);
+        assert!(contract.requests_by_status(RequestStatus::Open, None, None).is_empty());
+    }
+
+    #[test]
+    fn test_group_helpers_time_out_from_when_they_joined() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        register(&mut contract, &accounts(1));
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        contract.create_request("Enseñar NEAR a 5 personas".to_string(), None, None, None, None, Some(3));
+        for helper_id in [accounts(2), accounts(3), accounts(4)] {
+            apply_to_request(&mut context, &mut contract, helper_id, 0);
+        }
+        set_caller(&mut context, accounts(1));
+        contract.accept_application(0, accounts(2));
+        contract.accept_application(0, accounts(3));
+
+        // accounts(4) joins once the first helpers can already be released
+        testing_env!(context.block_timestamp(crate::RELEASE_HELPER_AFTER).build());
+        contract.accept_application(0, accounts(4));
+        assert_contract_panics(module_path!(), "test_group_helpers_time_out_from_when_they_joined", "Debes esperar 3 días", || {
+            contract.release_helper(0, accounts(4));
+        });
+
+        // only the named helper is released
+        contract.release_helper(0, accounts(2));
+        let request = contract.get_request(0).unwrap();
+        assert!(request.status == RequestStatus::InProgress);
+        assert_eq!(request.helper, Some(accounts(3)));
+        assert_eq!(request.participants.len(), 2);
+        assert_eq!(contract.get_reputation(accounts(2)).abandoned, 1);
+        assert_eq!(contract.get_reputation(accounts(3)).abandoned, 0);
+
+        // accounts(3) ran out of time, accounts(4) joined later and keeps working
+        testing_env!(context.block_timestamp(crate::DEFAULT_WORK_TIMEOUT).build());
+        contract.expire_request(0);
+        let request = contract.get_request(0).unwrap();
+        assert!(request.status == RequestStatus::InProgress);
+        assert_eq!(request.helper, Some(accounts(4)));
+        assert_eq!(request.participants.len(), 1);
+
+        testing_env!(context.block_timestamp(crate::RELEASE_HELPER_AFTER + crate::DEFAULT_WORK_TIMEOUT).build());
+        contract.expire_request(0);
+        let request = contract.get_request(0).unwrap();
+        assert!(request.status == RequestStatus::Open);
+        assert!(request.participants.is_empty());
+    }
+
+    #[test]
+    fn test_deleted_group_request_leaves_every_helper_index() {
+        let mut context = get_context(accounts(0));
+        let mut contract = contract_with_full_group_request(&mut context);
+        contract.open_dispute(0, "Nadie respondió".to_string());
+        set_caller(&mut context, accounts(0));
+        contract.add_arbiter(accounts(4));
+        set_caller(&mut context, accounts(4));
+        contract.resolve_dispute(0, DisputeResolution::OwnerCancel);
+
+        set_caller(&mut context, accounts(1));
+        contract.delete_request(0);
+        assert!(contract.requests_for_helper(accounts(2), None, None, None).is_empty());
+        assert!(contract.requests_for_helper(accounts(3), None, None, None).is_empty());
+    }
+}
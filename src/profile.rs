@@ -1,54 +1,438 @@
-use near_sdk::{
-    env, serde_json::json
-};
+use near_sdk::env;
 
 use crate::*;
 
-const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(10_000_000_000_000);
-const GAS_FOR_NFT_TRANSFER_CALL: Gas = Gas(25_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER.0);
 const MIN_GAS_FOR_NFT_TRANSFER_CALL: Gas = Gas(100_000_000_000_000);
 const NO_DEPOSIT: Balance = 0;
+//limits of the profile fields, lengths in characters
+pub const MAX_DISPLAY_NAME_LEN: usize = 64;
+pub const MAX_BIO_LEN: usize = 500;
+pub const MAX_SKILLS: usize = 10;
+pub const MAX_LANGUAGES: usize = 5;
+pub const MAX_LANGUAGE_LEN: usize = 32;
+pub const MAX_LINKS: usize = 5;
+pub const MAX_LINK_LEN: usize = 200;
+pub const MAX_TIMEZONE_LEN: usize = 64;
+
+//token pinned as the picture of a profile
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Avatar {
+    pub token_id: TokenId,
+    pub media: Option<String>,
+}
+
+//profile as returned by the views
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonProfile {
+    pub account_id: AccountId,
+    #[serde(flatten)]
+    pub profile: Profile,
+    //number of people the account helped
+    pub times_helped: u64,
+    pub avatar: Option<Avatar>,
+}
 
 #[near_bindgen]
 impl Contract {
-    pub fn create_profile(&mut self, email: String, bio: String) -> String {
+    /*
+        The account pays for the storage of its profile, refunded by delete_profile. Contact details are not
+        part of the profile, they are shared with the counterparts of a request through share_contact.
+    */
+    #[payable]
+    pub fn create_profile(&mut self, profile: Profile) -> String {
         let accountid = env::signer_account_id().clone();
         // Verificar que el perfil no exista
-        let p = self.profiles.get(&accountid.clone());
+        let p = self.internal_get_profile(&accountid);
         if p.is_some() {
             env::panic_str("Ya existe un perfil para esta cuenta");
         }
-        let new_profile = Profile {
-            email : email,
-            bio : bio,
-        };
-        self.profiles.insert(accountid.clone(),new_profile);
-
-        ext_nft::mint_swapti(
-            accountid.clone(),
-            "1000000000000000000000000".to_string(),
-            "dev-1678227171447-27568759288636".to_string().parse::<AccountId>().unwrap(),
-            NO_DEPOSIT,
-            MIN_GAS_FOR_NFT_TRANSFER_CALL
+        //banned accounts don't get the onboarding SWAPTI either
+        if self.internal_is_banned(&accountid) {
+            env::panic_str("La cuenta no tiene acceso al marketplace");
+        }
+        self.internal_validate_profile(&profile);
+
+        let initial_storage_usage = env::storage_usage();
+        //the onboarding entry is paid here but never refunded, it outlives the profile
+        let first_profile = self.onboarded.insert(&accountid);
+        let profile_storage_usage = env::storage_usage();
+        self.internal_set_profile(&accountid, profile);
+        //the deposit has a fixed size, so it is measured with the profile
+        self.profile_storage_deposits.insert(&accountid, &0);
+        let profile_storage_in_bytes = env::storage_usage() - profile_storage_usage;
+        self.profile_storage_deposits.insert(
+            &accountid,
+            &(env::storage_byte_cost() * Balance::from(profile_storage_in_bytes)),
         );
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        self.internal_log_profile_event(EventLogVariant::ProfileCreated(vec![ProfileLog {
+            account_id: accountid.to_string(),
+        }]));
 
-        return "Perfil creado con éxito".to_string();
+        //deleting the profile and creating it again doesn't mint another SWAPTI
+        if first_profile {
+            ext_nft::mint_swapti(
+                accountid.clone(),
+                "1000000000000000000000000".to_string(),
+                "dev-1678227171447-27568759288636".to_string().parse::<AccountId>().unwrap(),
+                NO_DEPOSIT,
+                MIN_GAS_FOR_NFT_TRANSFER_CALL
+            );
+        }
+
+        "Perfil creado con éxito".to_string()
     }
 
-    pub fn get_profile(&self, account_id: AccountId) -> Profile {
+    pub fn get_profile(&self, account_id: AccountId) -> JsonProfile {
         // Verificar que el perfil no exista
-        let p = self.profiles.get(&account_id.clone());
+        let p = self.internal_get_profile(&account_id);
         if p.is_none() {
             env::panic_str("No se encontró información de perfil");
         }
 
-        let info = p.unwrap();
+        self.internal_json_profile(account_id)
+    }
+
+    /*
+        Rewrites the profile of the caller. A longer profile charges the extra storage from the
+        attached deposit, a shorter one refunds the freed storage along with the deposit.
+    */
+    #[payable]
+    pub fn update_profile(&mut self, profile: Profile) -> String {
+        let account_id = env::signer_account_id();
+        if self.internal_get_profile(&account_id).is_none() {
+            env::panic_str("No se encontró información de perfil");
+        }
+        self.internal_validate_profile(&profile);
+
+        let initial_storage_usage = env::storage_usage();
+        self.internal_set_profile(&account_id, profile);
+        self.internal_settle_profile_storage(&account_id, initial_storage_usage);
+
+        self.internal_log_profile_event(EventLogVariant::ProfileUpdated(vec![ProfileLog {
+            account_id: account_id.to_string(),
+        }]));
 
-        let profile_info = Profile {
-            email : info.email.to_string(),
-            bio : info.bio.to_string()
+        "Perfil actualizado con éxito".to_string()
+    }
+
+    //profiles in the directory, optionally sorted by the number of people each account helped
+    pub fn get_profiles(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+        sort_by_helped: Option<bool>,
+    ) -> Vec<JsonProfile> {
+        if sort_by_helped.unwrap_or(false) {
+            let account_ids = self.profiles_by_helped.iter_rev().map(|((_, account_id), _)| account_id);
+            self.internal_profiles_page(account_ids, from_index, limit)
+        } else {
+            self.internal_profiles_page(self.profiles.keys(), from_index, limit)
+        }
+    }
+
+    //profiles that list the skill, optionally sorted by the number of people each account helped
+    pub fn profiles_by_skill(
+        &self,
+        skill: String,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+        sort_by_helped: Option<bool>,
+    ) -> Vec<JsonProfile> {
+        if sort_by_helped.unwrap_or(false) {
+            let skill_index = if let Some(skill_index) = self.profiles_by_helped_per_skill.get(&skill) {
+                skill_index
+            } else {
+                return vec![];
+            };
+            let account_ids = skill_index.iter_rev().map(|((_, account_id), _)| account_id);
+            return self.internal_profiles_page(account_ids, from_index, limit);
+        }
+
+        let account_set = if let Some(account_set) = self.accounts_per_skill.get(&skill) {
+            account_set
+        } else {
+            return vec![];
         };
 
-        return profile_info;
+        self.internal_profiles_page(account_set.iter(), from_index, limit)
+    }
+
+    //the contract owner manages the skills profiles can list
+    pub fn add_skill(&mut self, skill: String) {
+        self.assert_contract_owner();
+
+        if skill.is_empty() || skill.chars().count() > MAX_TAG_LEN {
+            env::panic_str(&format!("La habilidad debe tener entre 1 y {} caracteres", MAX_TAG_LEN));
+        }
+
+        self.skill_registry.insert(&skill);
+    }
+
+    //profiles that already list the skill keep it
+    pub fn remove_skill(&mut self, skill: String) {
+        self.assert_contract_owner();
+        self.skill_registry.remove(&skill);
+    }
+
+    pub fn get_skills(&self) -> Vec<String> {
+        self.skill_registry.to_vec()
+    }
+
+    //removes the profile of the caller and refunds its storage deposit, the help counters are kept
+    #[payable]
+    pub fn delete_profile(&mut self) -> String {
+        assert_one_yocto();
+        let account_id = env::signer_account_id();
+
+        let profile = self.internal_get_profile(&account_id)
+            .unwrap_or_else(|| env::panic_str("No se encontró información de perfil"));
+        for skill in profile.skills.iter() {
+            self.internal_remove_account_from_skill(skill, &account_id);
+        }
+        if self.profiles.get(&account_id).is_some() {
+            let times_helped = self.internal_get_times_helped(&account_id).unwrap_or_default().number;
+            self.internal_remove_from_helped_index(&account_id, &profile.skills, times_helped);
+        }
+        self.legacy_profiles.remove(&account_id);
+        self.profiles.remove(&account_id);
+
+        self.avatars.remove(&account_id);
+
+        //profiles created before the onboarded set existed are remembered here, out of their refund
+        let initial_storage_usage = env::storage_usage();
+        self.onboarded.insert(&account_id);
+        let onboarded_cost = env::storage_byte_cost() * Balance::from(env::storage_usage() - initial_storage_usage);
+        let storage_deposit = self.profile_storage_deposits.remove(&account_id)
+            .unwrap_or(0)
+            .saturating_sub(onboarded_cost);
+        if storage_deposit > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(storage_deposit);
+        }
+
+        self.internal_log_profile_event(EventLogVariant::ProfileDeleted(vec![ProfileLog {
+            account_id: account_id.to_string(),
+        }]));
+
+        "Perfil eliminado con éxito".to_string()
+    }
+
+    /*
+        Pins one of the caller's tokens as the picture of their profile. The pin is dropped when the
+        token leaves the account, the storage is charged like the rest of the profile.
+    */
+    #[payable]
+    pub fn set_avatar(&mut self, token_id: TokenId) -> JsonProfile {
+        let account_id = env::signer_account_id();
+        if self.internal_get_profile(&account_id).is_none() {
+            env::panic_str("No se encontró información de perfil");
+        }
+
+        let owns_token = self.tokens_per_owner.get(&account_id)
+            .is_some_and(|tokens| tokens.contains(&token_id));
+        if !owns_token {
+            env::panic_str("Solo puedes usar como avatar un token de tu propiedad");
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        self.avatars.insert(&account_id, &token_id);
+        self.internal_settle_profile_storage(&account_id, initial_storage_usage);
+
+        self.internal_json_profile(account_id)
+    }
+
+    #[payable]
+    pub fn clear_avatar(&mut self) -> JsonProfile {
+        let account_id = env::signer_account_id();
+        if self.internal_get_profile(&account_id).is_none() {
+            env::panic_str("No se encontró información de perfil");
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        self.avatars.remove(&account_id);
+        self.internal_settle_profile_storage(&account_id, initial_storage_usage);
+
+        self.internal_json_profile(account_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::{accounts, get_created_receipts};
+    use near_sdk::testing_env;
+
+    //mint_swapti calls made since the context was last set
+    fn onboarding_mints() -> usize {
+        get_created_receipts()
+            .iter()
+            .filter(|receipt| receipt.actions.iter().any(|action| {
+                matches!(action, VmAction::FunctionCall { function_name, .. } if function_name == "mint_swapti")
+            }))
+            .count()
+    }
+
+    #[test]
+    fn test_profile_update_and_delete() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        contract.create_profile(sample_profile("Rust"));
+        let initial_deposit = contract.profile_storage_deposits.get(&accounts(1)).unwrap();
+        assert!(initial_deposit > 0);
+
+        testing_env!(context.storage_usage(env::storage_usage()).build());
+        contract.update_profile(sample_profile("Rust, NEAR y JavaScript"));
+        assert_eq!(contract.get_profile(accounts(1)).profile.bio, "Rust, NEAR y JavaScript".to_string());
+        let grown_deposit = contract.profile_storage_deposits.get(&accounts(1)).unwrap();
+        assert!(grown_deposit > initial_deposit);
+
+        testing_env!(context.storage_usage(env::storage_usage()).attached_deposit(0).build());
+        contract.update_profile(sample_profile("Rust"));
+        assert_eq!(contract.profile_storage_deposits.get(&accounts(1)).unwrap(), initial_deposit);
+
+        testing_env!(context.attached_deposit(1).build());
+        contract.delete_profile();
+        assert!(contract.profiles.get(&accounts(1)).is_none());
+        assert!(contract.profile_storage_deposits.get(&accounts(1)).is_none());
+    }
+
+    #[test]
+    fn test_profile_fields_and_versions() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        contract.add_skill("Rust".to_string());
+        assert_eq!(contract.get_skills(), vec!["Rust".to_string()]);
+
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        contract.create_profile(
+            Profile {
+                display_name: "Ana".to_string(),
+                bio: "Backend".to_string(),
+                skills: vec!["Rust".to_string()],
+                languages: vec!["Español".to_string(), "English".to_string()],
+                links: vec!["https://github.com/ana".to_string()],
+                timezone: Some("America/Mexico_City".to_string()),
+            },
+        );
+        let profile = contract.get_profile(accounts(1)).profile;
+        assert_eq!(profile.skills, vec!["Rust".to_string()]);
+        assert_eq!(profile.timezone, Some("America/Mexico_City".to_string()));
+
+        // profiles of the first version are read with the new layout
+        contract.legacy_profiles.insert(
+            accounts(2),
+            ProfileV1 { email: "luis@swapti.com".to_string(), bio: "Frontend".to_string() },
+        );
+        let profile = contract.get_profile(accounts(2)).profile;
+        assert_eq!(profile.bio, "Frontend".to_string());
+        assert!(profile.skills.is_empty());
+        // the email is no longer part of the public profile
+        let profile_json = near_sdk::serde_json::to_string(&contract.get_profile(accounts(2))).unwrap();
+        assert!(!profile_json.contains("email"));
+    }
+
+    #[test]
+    fn test_profile_directory_and_skill_search() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        contract.add_skill("Rust".to_string());
+        contract.add_skill("JS".to_string());
+
+        for (account_id, skills, times_helped) in [
+            (accounts(1), vec!["Rust"], 2),
+            (accounts(2), vec!["Rust", "JS"], 7),
+            (accounts(3), vec!["JS"], 4),
+        ] {
+            set_caller_with_deposit(&mut context, account_id.clone(), MINT_STORAGE_COST);
+            let mut profile = sample_profile("Ayudo a aprender");
+            profile.skills = skills.into_iter().map(|skill| skill.to_string()).collect();
+            contract.create_profile(profile);
+            contract.internal_update_times_helped(&account_id, |helped| helped.number = times_helped);
+        }
+
+        assert_eq!(contract.get_profiles(None, None, None).len(), 3);
+        let by_help: Vec<AccountId> = contract.get_profiles(None, Some(2), Some(true))
+            .into_iter()
+            .map(|profile| profile.account_id)
+            .collect();
+        assert_eq!(by_help, vec![accounts(2), accounts(3)]);
+
+        let rust_helpers = contract.profiles_by_skill("Rust".to_string(), None, None, Some(true));
+        assert_eq!(rust_helpers.len(), 2);
+        assert_eq!(rust_helpers[0].account_id, accounts(2));
+        assert_eq!(rust_helpers[0].times_helped, 7);
+
+        // dropping a skill takes the account out of its index
+        testing_env!(context.storage_usage(env::storage_usage()).build());
+        contract.update_profile(sample_profile("Solo JS"));
+        let js_helpers = contract.profiles_by_skill("JS".to_string(), None, None, None);
+        assert_eq!(js_helpers.len(), 1);
+        assert_eq!(js_helpers[0].account_id, accounts(2));
+        let js_helpers = contract.profiles_by_skill("JS".to_string(), None, None, Some(true));
+        assert_eq!(js_helpers.len(), 1);
+        assert_eq!(js_helpers[0].account_id, accounts(2));
+
+        // helping more people moves the account up the sorted directory
+        contract.internal_update_times_helped(&accounts(1), |helped| helped.number = 9);
+        let by_help: Vec<AccountId> = contract.get_profiles(None, None, Some(true))
+            .into_iter()
+            .map(|profile| profile.account_id)
+            .collect();
+        assert_eq!(by_help, vec![accounts(1), accounts(2), accounts(3)]);
+    }
+
+    #[test]
+    fn test_avatar_pin_cleared_on_transfer() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        register(&mut contract, &accounts(1));
+
+        // a badge held by accounts(1)
+        let token_id = "bronce-1".to_string();
+        contract.tokens_by_id.insert(&token_id, &Token {
+            owner_id: accounts(1),
+            approved_account_ids: HashMap::new(),
+            next_approval_id: 0,
+            royalty: HashMap::new(),
+        });
+        contract.token_metadata_by_id.insert(&token_id, &TokenMetadata {
+            media: Some("bronce.png".to_string()),
+            ..sample_token_metadata()
+        });
+        contract.internal_add_token_to_owner(&accounts(1), &token_id);
+
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        let avatar = contract.set_avatar(token_id.clone()).avatar.unwrap();
+        assert_eq!(avatar.token_id, token_id);
+        assert_eq!(avatar.media, Some("bronce.png".to_string()));
+
+        // the pin goes away with the token
+        testing_env!(context.attached_deposit(1).build());
+        crate::nft_core::NonFungibleTokenCore::nft_transfer(&mut contract, accounts(2), token_id, None, None);
+        assert!(contract.get_profile(accounts(1)).avatar.is_none());
+    }
+
+    #[test]
+    fn test_recreated_profile_gets_no_onboarding_mint() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        contract.create_profile(sample_profile("Rust"));
+        assert_eq!(onboarding_mints(), 1);
+
+        testing_env!(context.attached_deposit(1).build());
+        contract.delete_profile();
+        assert!(contract.onboarded.contains(&accounts(1)));
+
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        contract.create_profile(sample_profile("Rust"));
+        assert_eq!(onboarding_mints(), 0);
+        assert_eq!(contract.get_profile(accounts(1)).profile.bio, "Rust".to_string());
     }
-}
\ No newline at end of file
+}
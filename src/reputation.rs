@@ -0,0 +1,96 @@
+use crate::*;
+
+//running counters kept for every account that took part in a request
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct Reputation {
+    //sum and count of the ratings received as helper
+    pub helper_rating_sum: u64,
+    pub helper_rating_count: u64,
+    //sum and count of the ratings received as requester
+    pub requester_rating_sum: u64,
+    pub requester_rating_count: u64,
+    //requests taken as helper
+    pub requests_attended: u64,
+    //requests finished as helper
+    pub requests_completed: u64,
+    //requests the account stopped working on after taking them
+    pub abandoned: u64,
+    //disputes the account took part in
+    pub disputes: u64,
+}
+
+//reputation as returned by get_reputation
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonReputation {
+    pub account_id: AccountId,
+    //average rating times 100 (450 = 4.5 stars), null until the account is rated
+    pub avg_rating_as_helper: Option<u64>,
+    pub ratings_as_helper: u64,
+    pub avg_rating_as_requester: Option<u64>,
+    pub ratings_as_requester: u64,
+    pub requests_attended: u64,
+    pub requests_completed: u64,
+    //percentage of attended requests that were completed, null until the account attends one
+    pub completion_rate: Option<u64>,
+    pub abandoned: u64,
+    pub disputes: u64,
+}
+
+//average times 100 of the ratings, if there is any
+fn average_x100(sum: u64, count: u64) -> Option<u64> {
+    (sum * 100).checked_div(count)
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_reputation(&self, account_id: AccountId) -> JsonReputation {
+        let reputation = self.reputations.get(&account_id).unwrap_or_default();
+
+        JsonReputation {
+            account_id,
+            avg_rating_as_helper: average_x100(reputation.helper_rating_sum, reputation.helper_rating_count),
+            ratings_as_helper: reputation.helper_rating_count,
+            avg_rating_as_requester: average_x100(reputation.requester_rating_sum, reputation.requester_rating_count),
+            ratings_as_requester: reputation.requester_rating_count,
+            requests_attended: reputation.requests_attended,
+            requests_completed: reputation.requests_completed,
+            completion_rate: (reputation.requests_completed * 100).checked_div(reputation.requests_attended),
+            abandoned: reputation.abandoned,
+            disputes: reputation.disputes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+
+    #[test]
+    fn test_reputation_follows_request_lifecycle() {
+        let mut context = get_context(accounts(0));
+        let mut contract = contract_with_attended_request(&mut context);
+        assert_eq!(contract.get_reputation(accounts(2)).completion_rate, Some(0));
+
+        set_caller(&mut context, accounts(1));
+        contract.finish_request(0);
+        commit_review(&mut contract, 0, 5, "Excelente ayuda", None);
+        set_caller(&mut context, accounts(2));
+        commit_review(&mut contract, 0, 4, "Solicitud clara", None);
+        // committed ratings don't count until they are revealed
+        assert_eq!(contract.get_reputation(accounts(2)).avg_rating_as_helper, None);
+
+        reveal_review(&mut contract, 0, 4, "Solicitud clara", None);
+        set_caller(&mut context, accounts(1));
+        reveal_review(&mut contract, 0, 5, "Excelente ayuda", None);
+
+        let helper = contract.get_reputation(accounts(2));
+        assert_eq!(helper.requests_attended, 1);
+        assert_eq!(helper.requests_completed, 1);
+        assert_eq!(helper.completion_rate, Some(100));
+        assert_eq!(helper.avg_rating_as_helper, Some(500));
+        let owner = contract.get_reputation(accounts(1));
+        assert_eq!(owner.avg_rating_as_requester, Some(400));
+    }
+}
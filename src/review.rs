@@ -0,0 +1,305 @@
+use crate::*;
+
+//ratings go from 1 to 5 stars
+pub const MIN_RATING: u8 = 1;
+pub const MAX_RATING: u8 = 5;
+//max length (in characters) of a review comment
+pub const MAX_REVIEW_COMMENT_LEN: usize = 280;
+//time both sides have to review each other after the request is finished (7 days in nanoseconds)
+pub const REVIEW_WINDOW: u64 = 7 * 24 * 60 * 60 * 1_000_000_000;
+
+//a review as returned by the per account views
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonReview {
+    pub request_id: u64,
+    pub reviewer_id: AccountId,
+    pub reviewee_id: AccountId,
+    pub rating: u8,
+    pub comment: String,
+}
+
+//sha256 of the rating byte followed by the comment and the salt, the hash a review is committed with
+pub fn review_commitment(rating: u8, comment: &str, salt: &str) -> Vec<u8> {
+    let mut preimage = vec![rating];
+    preimage.extend_from_slice(comment.as_bytes());
+    preimage.extend_from_slice(salt.as_bytes());
+    env::sha256(&preimage)
+}
+
+#[near_bindgen]
+impl Contract {
+    /*
+        The owner reviews every helper of the request and each helper reviews the owner, once each. Reviews are
+        sent as a commitment, the sha256 of the rating byte, the comment and a secret salt (see review_commitment),
+        so nobody can read the other side's review before writing theirs. The owner of a group request names the
+        helper they review, it defaults to the first one.
+    */
+    pub fn submit_review(&mut self, request_id: u64, commitment: Base64VecU8, helper_id: Option<AccountId>) -> String {
+        let mut request = self.requests.get(&request_id)
+            .unwrap_or_else(|| env::panic_str("No existe la solicitud a calificar"));
+
+        if request.status != RequestStatus::Complete {
+            env::panic_str("Solo se pueden calificar solicitudes finalizadas");
+        }
+
+        if commitment.0.len() != 32 {
+            env::panic_str("La calificación se envía como un hash sha256 de 32 bytes");
+        }
+
+        if self.internal_review_window_closed(&request) {
+            env::panic_str("El periodo para calificar esta solicitud terminó");
+        }
+
+        let reviewer_id = env::signer_account_id();
+
+        let reviewee_id = if reviewer_id == request.owner_id {
+            let helper_id = helper_id.or_else(|| request.helper.clone())
+                .unwrap_or_else(|| env::panic_str("La solicitud no tiene quien la atienda"));
+            let participant = request.participants.iter_mut()
+                .find(|participant| participant.account_id == helper_id && participant.completed)
+                .unwrap_or_else(|| env::panic_str("La cuenta no ayudó en esta solicitud"));
+            if participant.commitment_owner.is_some() {
+                env::panic_str("Ya calificaste a este ayudante");
+            }
+            participant.commitment_owner = Some(commitment);
+            helper_id
+        } else if let Some(participant) = request.participants.iter_mut()
+            .find(|participant| participant.account_id == reviewer_id && participant.completed)
+        {
+            if participant.commitment_helper.is_some() {
+                env::panic_str("Ya calificaste esta solicitud");
+            }
+            participant.commitment_helper = Some(commitment);
+            request.owner_id.clone()
+        } else {
+            env::panic_str("Solo el creador y quienes atendieron la solicitud pueden calificarla");
+        };
+
+        self.requests.insert(&request_id, &request);
+
+        //the content of the review is only logged once it is revealed
+        let review_log: EventLog = EventLog {
+            standard: SWAPTI_STANDARD_NAME.to_string(),
+            version: SWAPTI_EVENT_SPEC.to_string(),
+            event: EventLogVariant::ReviewSubmitted(vec![ReviewSubmittedLog {
+                request_id,
+                reviewer_id: reviewer_id.to_string(),
+                reviewee_id: reviewee_id.to_string(),
+            }]),
+        };
+        env::log_str(&review_log.to_string());
+
+        "Calificación enviada con éxito".to_string()
+    }
+
+    /*
+        The reviewer publishes the review they committed to, once the other side of the pair committed theirs
+        or the review window is over. The rating and the comment are checked here, their hash must match the
+        commitment. The owner of a group request names the helper the review is about.
+    */
+    pub fn reveal_review(
+        &mut self,
+        request_id: u64,
+        rating: u8,
+        comment: String,
+        salt: String,
+        helper_id: Option<AccountId>,
+    ) -> String {
+        let mut request = self.requests.get(&request_id)
+            .unwrap_or_else(|| env::panic_str("No existe la solicitud"));
+
+        if request.status != RequestStatus::Complete {
+            env::panic_str("Solo se pueden calificar solicitudes finalizadas");
+        }
+
+        let window_closed = self.internal_review_window_closed(&request);
+        let reviewer_id = env::signer_account_id();
+        let owner_id = request.owner_id.clone();
+        let is_owner = reviewer_id == owner_id;
+        let helper_id = if is_owner {
+            helper_id.or_else(|| request.helper.clone())
+                .unwrap_or_else(|| env::panic_str("La solicitud no tiene quien la atienda"))
+        } else {
+            reviewer_id.clone()
+        };
+
+        let participant = request.participants.iter_mut()
+            .find(|participant| participant.account_id == helper_id && participant.completed)
+            .unwrap_or_else(|| env::panic_str("Solo el creador y quienes atendieron la solicitud pueden calificarla"));
+
+        let (commitment, review) = if is_owner {
+            (&participant.commitment_owner, &participant.review_owner)
+        } else {
+            (&participant.commitment_helper, &participant.review_helper)
+        };
+        let commitment = commitment.as_ref()
+            .unwrap_or_else(|| env::panic_str("No enviaste una calificación para revelar"));
+        if review.is_some() {
+            env::panic_str("Ya revelaste esta calificación");
+        }
+
+        if !window_closed && (participant.commitment_owner.is_none() || participant.commitment_helper.is_none()) {
+            env::panic_str("La otra parte aún no califica y el periodo para calificar no termina");
+        }
+
+        if !(MIN_RATING..=MAX_RATING).contains(&rating) {
+            env::panic_str("La calificación debe estar entre 1 y 5");
+        }
+
+        if comment.chars().count() > MAX_REVIEW_COMMENT_LEN {
+            env::panic_str(&format!("El comentario no puede superar {} caracteres", MAX_REVIEW_COMMENT_LEN));
+        }
+
+        if review_commitment(rating, &comment, &salt) != commitment.0 {
+            env::panic_str("La calificación no coincide con la enviada");
+        }
+
+        let review = Review { rating, comment };
+        let reviewee_id = if is_owner {
+            participant.review_owner = Some(review.clone());
+            helper_id
+        } else {
+            participant.review_helper = Some(review.clone());
+            owner_id
+        };
+        self.requests.insert(&request_id, &request);
+
+        self.internal_publish_review(&request_id, &reviewee_id, &review, is_owner);
+
+        let reveal_log: EventLog = EventLog {
+            standard: SWAPTI_STANDARD_NAME.to_string(),
+            version: SWAPTI_EVENT_SPEC.to_string(),
+            event: EventLogVariant::ReviewRevealed(vec![ReviewRevealedLog {
+                request_id,
+                reviewer_id: reviewer_id.to_string(),
+                reviewee_id: reviewee_id.to_string(),
+                rating: review.rating,
+                comment: review.comment,
+            }]),
+        };
+        env::log_str(&reveal_log.to_string());
+
+        "Calificación publicada con éxito".to_string()
+    }
+
+    //reviews received by an account, only the ones that were already revealed
+    pub fn reviews_for_account(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonReview> {
+        let reviews_set = if let Some(reviews_set) = self.reviews_per_account.get(&account_id) {
+            reviews_set
+        } else {
+            return vec![];
+        };
+
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        reviews_set.iter()
+            .filter_map(|request_id| self.get_request(request_id))
+            .flat_map(|request| {
+                //the owner is reviewed by every helper and each helper by the owner
+                let reviews: Vec<(AccountId, Review)> = if request.owner_id == account_id {
+                    request.participants.into_iter()
+                        .filter_map(|participant| Some((participant.account_id, participant.review_helper?)))
+                        .collect()
+                } else {
+                    request.participants.into_iter()
+                        .filter(|participant| participant.account_id == account_id)
+                        .filter_map(|participant| Some((request.owner_id.clone(), participant.review_owner?)))
+                        .collect()
+                };
+                reviews.into_iter()
+                    .map(|(reviewer_id, review)| JsonReview {
+                        request_id: request.request_id,
+                        reviewer_id,
+                        reviewee_id: account_id.clone(),
+                        rating: review.rating,
+                        comment: review.comment,
+                    })
+                    .collect::<Vec<JsonReview>>()
+            })
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn test_reviews_revealed_once_both_sides_commit() {
+        let mut context = get_context(accounts(0));
+        let mut contract = contract_with_attended_request(&mut context);
+        set_caller(&mut context, accounts(1));
+        contract.finish_request(0);
+
+        commit_review(&mut contract, 0, 5, "Excelente ayuda", None);
+        // the helper did not commit yet
+        assert_contract_panics(module_path!(), "test_reviews_revealed_once_both_sides_commit", "aún no califica", || {
+            reveal_review(&mut contract, 0, 5, "Excelente ayuda", None);
+        });
+
+        set_caller(&mut context, accounts(2));
+        commit_review(&mut contract, 0, 4, "Solicitud clara", None);
+        // the revealed review must be the committed one
+        assert_contract_panics(module_path!(), "test_reviews_revealed_once_both_sides_commit", "no coincide", || {
+            reveal_review(&mut contract, 0, 5, "Solicitud clara", None);
+        });
+        reveal_review(&mut contract, 0, 4, "Solicitud clara", None);
+        assert!(contract.get_request(0).unwrap().participants[0].review_owner.is_none());
+        assert_eq!(contract.reviews_for_account(accounts(1), None, None)[0].rating, 4);
+
+        set_caller(&mut context, accounts(1));
+        reveal_review(&mut contract, 0, 5, "Excelente ayuda", None);
+        let reviews = contract.reviews_for_account(accounts(2), None, None);
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].reviewer_id, accounts(1));
+        assert_eq!(reviews[0].comment, "Excelente ayuda");
+    }
+
+    #[test]
+    fn test_review_revealed_after_review_window() {
+        let mut context = get_context(accounts(0));
+        let mut contract = contract_with_attended_request(&mut context);
+        set_caller(&mut context, accounts(1));
+        contract.finish_request(0);
+        commit_review(&mut contract, 0, 3, "", None);
+
+        testing_env!(context.block_timestamp(crate::REVIEW_WINDOW + 1).build());
+        reveal_review(&mut contract, 0, 3, "", None);
+        assert_eq!(contract.get_request(0).unwrap().participants[0].review_owner.as_ref().unwrap().rating, 3);
+    }
+
+    #[test]
+    fn test_invalid_reviews_are_rejected() {
+        let mut context = get_context(accounts(0));
+        let mut contract = contract_with_attended_request(&mut context);
+        set_caller(&mut context, accounts(1));
+        contract.finish_request(0);
+        let long_comment = "a".repeat(crate::MAX_REVIEW_COMMENT_LEN + 1);
+        commit_review(&mut contract, 0, 6, &long_comment, None);
+
+        // each side reviews once
+        assert_contract_panics(module_path!(), "test_invalid_reviews_are_rejected", "Ya calificaste", || {
+            commit_review(&mut contract, 0, 5, "Excelente ayuda", None);
+        });
+
+        set_caller(&mut context, accounts(2));
+        commit_review(&mut contract, 0, 4, "Solicitud clara", None);
+        set_caller(&mut context, accounts(1));
+        assert_contract_panics(module_path!(), "test_invalid_reviews_are_rejected", "entre 1 y 5", || {
+            reveal_review(&mut contract, 0, 6, &long_comment, None);
+        });
+        assert_contract_panics(module_path!(), "test_invalid_reviews_are_rejected", "no puede superar", || {
+            reveal_review(&mut contract, 0, 5, &long_comment, None);
+        });
+    }
+}
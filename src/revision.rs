@@ -0,0 +1,137 @@
+use crate::*;
+
+//max number of previous descriptions kept for a request
+pub const MAX_REVISIONS_PER_REQUEST: u64 = 20;
+
+//a previous description of a request
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Revision {
+    pub description: String,
+    //when this description was replaced by a newer one
+    pub replaced_at: U64,
+}
+
+#[near_bindgen]
+impl Contract {
+    /*
+        The owner rewrites the description while nobody attends the request. The previous description is
+        kept in the revision history and the owner pays for the extra storage.
+    */
+    #[payable]
+    pub fn update_request(&mut self, request_id: u64, description: String) -> Option<Request> {
+        if let Some(request_data) = self.requests.get(&request_id) {
+            if request_data.owner_id != env::signer_account_id() {
+                env::panic_str("Solo el creador de la solicitud puede editarla");
+            }
+
+            //once a helper is assigned the description is frozen
+            if request_data.status != RequestStatus::Open {
+                env::panic_str("Solo se pueden editar solicitudes abiertas");
+            }
+
+            self.assert_request_not_frozen(&request_data);
+
+            let initial_storage_usage = env::storage_usage();
+
+            let mut revisions = self.revisions_per_request.get(&request_id).unwrap_or_else(|| {
+                Vector::new(
+                    StorageKey::RevisionsPerRequestInner { request_id }
+                        .try_to_vec()
+                        .unwrap(),
+                )
+            });
+            if revisions.len() >= MAX_REVISIONS_PER_REQUEST {
+                env::panic_str(&format!("Una solicitud se puede editar hasta {} veces", MAX_REVISIONS_PER_REQUEST));
+            }
+            revisions.push(&Revision {
+                description: request_data.description.clone(),
+                replaced_at: U64(env::block_timestamp()),
+            });
+            self.revisions_per_request.insert(&request_id, &revisions);
+
+            let mut new_request = Request {
+                description,
+                ..request_data
+            };
+            self.requests.insert(&request_id, &new_request);
+
+            //a shorter description frees storage, which stays with the contract
+            let required_storage_in_bytes = env::storage_usage().saturating_sub(initial_storage_usage);
+            new_request.storage_deposit = U128(
+                new_request.storage_deposit.0 + env::storage_byte_cost() * Balance::from(required_storage_in_bytes),
+            );
+            self.requests.insert(&request_id, &new_request);
+
+            let update_log: EventLog = EventLog {
+                standard: SWAPTI_STANDARD_NAME.to_string(),
+                version: SWAPTI_EVENT_SPEC.to_string(),
+                event: EventLogVariant::RequestUpdated(vec![RequestUpdatedLog {
+                    request_id,
+                    revision: revisions.len(),
+                    description: new_request.description.clone(),
+                }]),
+            };
+            env::log_str(&update_log.to_string());
+
+            //refund any excess storage if the user attached too much. Panic if they didn't attach enough to cover the required.
+            refund_deposit(required_storage_in_bytes);
+
+            Some(new_request)
+        } else {
+            env::panic_str("No existe la solicitud a editar");
+        }
+    }
+
+    //previous descriptions of a request, oldest first
+    pub fn get_request_revisions(
+        &self,
+        request_id: u64,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Revision> {
+        let revisions = if let Some(revisions) = self.revisions_per_request.get(&request_id) {
+            revisions
+        } else {
+            return vec![];
+        };
+
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        revisions.iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+    use near_sdk::json_types::U64;
+    use near_sdk::test_utils::accounts;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn test_update_request_keeps_revisions() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        create_request_as(&mut context, &mut contract, accounts(1), "Necesito ayuda con JS");
+        let initial_deposit = contract.get_request(0).unwrap().storage_deposit.0;
+
+        testing_env!(context.block_timestamp(10).build());
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        contract.update_request(0, "Necesito ayuda con JS y React".to_string());
+        contract.update_request(0, "Necesito ayuda con React".to_string());
+
+        let request = contract.get_request(0).unwrap();
+        assert_eq!(request.description, "Necesito ayuda con React".to_string());
+        assert!(request.storage_deposit.0 > initial_deposit);
+
+        let revisions = contract.get_request_revisions(0, None, None);
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[0].description, "Necesito ayuda con JS".to_string());
+        assert_eq!(revisions[1].description, "Necesito ayuda con JS y React".to_string());
+        assert_eq!(revisions[0].replaced_at, U64(10));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::*;
+
+//max number of tags a request can have
+pub const MAX_TAGS_PER_REQUEST: usize = 5;
+//max length (in characters) of a tag in the registry
+pub const MAX_TAG_LEN: usize = 32;
+
+#[near_bindgen]
+impl Contract {
+    //the contract owner manages the tags requests can use
+    pub fn add_tag(&mut self, tag: String) {
+        self.assert_contract_owner();
+
+        if tag.is_empty() || tag.chars().count() > MAX_TAG_LEN {
+            env::panic_str(&format!("La etiqueta debe tener entre 1 y {} caracteres", MAX_TAG_LEN));
+        }
+
+        self.tag_registry.insert(&tag);
+    }
+
+    //requests that already use the tag keep it and stay in its index
+    pub fn remove_tag(&mut self, tag: String) {
+        self.assert_contract_owner();
+        self.tag_registry.remove(&tag);
+    }
+
+    pub fn get_tags(&self) -> Vec<String> {
+        self.tag_registry.to_vec()
+    }
+
+    //requests with the given tag, optionally filtered by status
+    pub fn requests_by_tag(
+        &self,
+        tag: String,
+        status: Option<RequestStatus>,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Request> {
+        let request_set = if let Some(request_set) = self.requests_per_tag.get(&tag) {
+            request_set
+        } else {
+            return vec![];
+        };
+
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        request_set.iter()
+            .map(|request_id| self.get_request(request_id).unwrap())
+            .filter(|request| status.is_none() || status.as_ref() == Some(&request.status))
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::accounts;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn test_requests_by_tag() {
+        let mut context = get_context(accounts(0));
+        let mut contract = new_contract(&mut context);
+        register(&mut contract, &accounts(1));
+        contract.add_tag("Rust".to_string());
+        contract.add_tag("JS".to_string());
+        assert_eq!(contract.get_tags().len(), 2);
+
+        set_caller_with_deposit(&mut context, accounts(1), MINT_STORAGE_COST);
+        contract.create_request(
+            "Necesito ayuda con Rust".to_string(),
+            None,
+            None,
+            None,
+            Some(vec!["Rust".to_string(), "Rust".to_string()]),
+            None,
+        );
+        contract.create_request(
+            "Necesito ayuda con Rust y JS".to_string(),
+            None,
+            None,
+            None,
+            Some(vec!["Rust".to_string(), "JS".to_string()]),
+            None,
+        );
+        testing_env!(context.attached_deposit(0).build());
+        contract.cancel_request(1);
+
+        assert_eq!(contract.get_request(0).unwrap().tags, vec!["Rust".to_string()]);
+        assert_eq!(contract.requests_by_tag("Rust".to_string(), None, None, None).len(), 2);
+        let open_rust = contract.requests_by_tag("Rust".to_string(), Some(RequestStatus::Open), None, None);
+        assert_eq!(open_rust.len(), 1);
+        assert_eq!(open_rust[0].request_id, 0);
+        assert!(contract.requests_by_tag("JS".to_string(), Some(RequestStatus::Open), None, None).is_empty());
+        assert!(contract.requests_by_tag("Go".to_string(), None, None, None).is_empty());
+    }
+}
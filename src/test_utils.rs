@@ -0,0 +1,151 @@
+/* fixtures shared by the unit tests of every module */
+use crate::*;
+use near_sdk::test_utils::{accounts, VMContextBuilder};
+use near_sdk::testing_env;
+
+pub(crate) const MINT_STORAGE_COST: u128 = 100_000_000_000_000_000_000_000;
+pub(crate) const REVIEW_SALT: &str = "sal-secreta";
+//set in the child process that runs a call expected to panic
+const EXPECTED_PANIC_VAR: &str = "SWAPTI_EXPECTED_PANIC";
+
+/*
+    Contract panics abort the test binary (see test_default), so the test runs again in a child process
+    where only the call expecting `expected` is made. The other calls expected to panic are skipped there,
+    like a failed transaction that leaves the state untouched. Each call in a test expects its own message.
+*/
+pub(crate) fn assert_contract_panics<F: FnOnce()>(module: &str, test_name: &str, expected: &str, call: F) {
+    assert_contract_panics_case(module, test_name, expected, expected, call);
+}
+
+//same as assert_contract_panics for calls expecting the same message, each one named by its own `case`
+pub(crate) fn assert_contract_panics_case<F: FnOnce()>(module: &str, test_name: &str, case: &str, expected: &str, call: F) {
+    match std::env::var(EXPECTED_PANIC_VAR) {
+        Ok(child_case) if child_case == case => {
+            call();
+            return;
+        }
+        Ok(_) => return,
+        Err(_) => {}
+    }
+
+    //test names don't carry the crate name
+    let module = module.split_once("::").map_or("", |(_, path)| path);
+    let test_path = format!("{}::{}", module, test_name);
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([test_path.as_str(), "--exact", "--nocapture", "--test-threads=1"])
+        .env(EXPECTED_PANIC_VAR, case)
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !output.status.success() && stderr.contains(expected),
+        "{} should panic with \"{}\":\n{}",
+        test_path,
+        expected,
+        stderr
+    );
+}
+
+pub(crate) fn get_context(predecessor: AccountId) -> VMContextBuilder {
+    let mut builder = VMContextBuilder::new();
+    builder.predecessor_account_id(predecessor);
+    builder
+}
+
+//accounts(0) deploys the contract and owns it
+pub(crate) fn new_contract(context: &mut VMContextBuilder) -> Contract {
+    testing_env!(context.build());
+    Contract::new_default_meta(accounts(0))
+}
+
+pub(crate) fn set_caller(context: &mut VMContextBuilder, account_id: AccountId) {
+    testing_env!(context
+        .signer_account_id(account_id.clone())
+        .predecessor_account_id(account_id)
+        .build());
+}
+
+//the storage used by the next call is measured from the current usage
+pub(crate) fn set_caller_with_deposit(context: &mut VMContextBuilder, account_id: AccountId, deposit: Balance) {
+    testing_env!(context
+        .signer_account_id(account_id.clone())
+        .predecessor_account_id(account_id)
+        .storage_usage(env::storage_usage())
+        .attached_deposit(deposit)
+        .build());
+}
+
+//accounts(1) creates request 0 and picks accounts(2) to attend it
+pub(crate) fn contract_with_attended_request(context: &mut VMContextBuilder) -> Contract {
+    let mut contract = new_contract(context);
+    create_request_as(context, &mut contract, accounts(1), "Necesito ayuda con Rust");
+    apply_to_request(context, &mut contract, accounts(2), 0);
+    set_caller(context, accounts(1));
+    contract.accept_application(0, accounts(2));
+    contract
+}
+
+//request entry points need a profile, stored directly to skip the onboarding mint
+pub(crate) fn register(contract: &mut Contract, account_id: &AccountId) {
+    if contract.internal_get_profile(account_id).is_none() {
+        contract.internal_set_profile(account_id, sample_profile("Hola"));
+    }
+}
+
+//the owner attaches enough to cover the storage of a request without bounty
+pub(crate) fn create_request_as(context: &mut VMContextBuilder, contract: &mut Contract, owner: AccountId, description: &str) {
+    register(contract, &owner);
+    set_caller_with_deposit(context, owner, MINT_STORAGE_COST);
+    contract.create_request(description.to_string(), None, None, None, None, None);
+    testing_env!(context.attached_deposit(0).build());
+}
+
+pub(crate) fn apply_to_request(context: &mut VMContextBuilder, contract: &mut Contract, applicant: AccountId, request_id: u64) {
+    register(contract, &applicant);
+    set_caller_with_deposit(context, applicant, MINT_STORAGE_COST);
+    contract.attend_request(request_id, Some("Sé mucho de Rust".to_string()));
+    testing_env!(context.attached_deposit(0).build());
+}
+
+//reviews in the tests are committed and revealed with the same salt
+pub(crate) fn commit_review(contract: &mut Contract, request_id: u64, rating: u8, comment: &str, helper_id: Option<AccountId>) {
+    contract.submit_review(request_id, review_commitment(rating, comment, REVIEW_SALT).into(), helper_id);
+}
+
+pub(crate) fn reveal_review(contract: &mut Contract, request_id: u64, rating: u8, comment: &str, helper_id: Option<AccountId>) {
+    contract.reveal_review(request_id, rating, comment.to_string(), REVIEW_SALT.to_string(), helper_id);
+}
+
+//the amount ft_on_transfer hands back to the token contract
+pub(crate) fn unused_tokens(result: PromiseOrValue<U128>) -> U128 {
+    match result {
+        PromiseOrValue::Value(unused) => unused,
+        _ => panic!("expected a value"),
+    }
+}
+
+pub(crate) fn sample_profile(bio: &str) -> Profile {
+    Profile {
+        display_name: "Ana".to_string(),
+        bio: bio.to_string(),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn sample_token_metadata() -> TokenMetadata {
+    TokenMetadata {
+        title: Some("Olympus Mons".into()),
+        description: Some("The tallest mountain in the charted solar system".into()),
+        media: None,
+        media_hash: None,
+        copies: Some(1u64),
+        issued_at: None,
+        expires_at: None,
+        starts_at: None,
+        updated_at: None,
+        extra: None,
+        reference: None,
+        reference_hash: None,
+    }
+}
@@ -1,10 +1,5 @@
 use crate::*;
-use near_sdk::{ext_contract, Gas, PromiseResult};
-
-const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(10_000_000_000_000);
-const GAS_FOR_NFT_TRANSFER_CALL: Gas = Gas(25_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER.0);
-const MIN_GAS_FOR_NFT_TRANSFER_CALL: Gas = Gas(100_000_000_000_000);
-const NO_DEPOSIT: Balance = 0;
+use near_sdk::ext_contract;
 
 #[ext_contract(ext_nft)]
 pub trait ExternsContract {
@@ -12,7 +7,8 @@ pub trait ExternsContract {
     fn ft_transfer(&self,receiver_id: AccountId, amount: String) -> String;
 }
 
-#[ext_contract(ext_self)]
-trait NonFungibleTokenResolver {
-    
-}
\ No newline at end of file
+//NEP-141 fungible token contracts holding request bounties
+#[ext_contract(ext_ft)]
+pub trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}